@@ -31,6 +31,15 @@ export default function Lobby(props) {
         } catch (err) {
           console.error("Failed to parse game_created:", err);
         }
+      } else if (data.kind === "create_game_error") {
+        try {
+          const payload = JSON.parse(data.data);
+          if (payload.creator === creator()) {
+            setGameError(payload.error);
+          }
+        } catch (err) {
+          console.error("Failed to parse create_game_error:", err);
+        }
       }
     } catch (err) {
       // ignore
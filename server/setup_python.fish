@@ -1,5 +1,5 @@
 #!/usr/bin/env fish
-# Setup Python virtual environment for spaCy (Fish shell version)
+# Setup Python virtual environment for the optional spaCy lemmatizer (Fish shell version)
 
 echo "Creating Python virtual environment..."
 python3 -m venv venv
@@ -19,6 +19,6 @@ echo "✓ Setup complete!"
 echo ""
 echo "To use this environment:"
 echo "  source venv/bin/activate.fish    # Activate the venv"
-echo "  cargo test                       # Run tests"
-echo "  cargo run                        # Run server"
+echo "  cargo test --features spacy      # Run tests"
+echo "  cargo run --features spacy       # Run server"
 echo "  deactivate                       # Exit venv when done"
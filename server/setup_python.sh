@@ -1,5 +1,5 @@
 #!/bin/bash
-# Setup Python virtual environment for spaCy
+# Setup Python virtual environment for the optional spaCy lemmatizer (`--features spacy`)
 
 set -e
 
@@ -20,7 +20,7 @@ echo ""
 echo "✓ Setup complete!"
 echo ""
 echo "To use this environment:"
-echo "  source venv/bin/activate    # Activate the venv"
-echo "  cargo test                  # Run tests"
-echo "  cargo run                   # Run server"
-echo "  deactivate                  # Exit venv when done"
+echo "  source venv/bin/activate       # Activate the venv"
+echo "  cargo test --features spacy    # Run tests"
+echo "  cargo run --features spacy     # Run server"
+echo "  deactivate                     # Exit venv when done"
@@ -2,117 +2,45 @@ use crate::game::{player::Player, GameState};
 use lazy_static::lazy_static;
 use std::sync::RwLock;
 use std::{
-    collections::{BTreeSet, HashMap},
-    io::BufRead,
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    sync::{mpsc, Arc, Mutex},
+    time::Instant,
 };
 
+use super::anagrams_bot::AnagramsBot;
+use super::anagrams_solver::{self, DealSnapshot, HintSettings, MissedDeal, Play};
+use super::challenge::{self, ChallengeSettings};
+use super::deal_mode::{self, DealMode};
+use super::dictionary::{self, Dictionary};
+use super::endgame::{EndgameSettings, FinalCountdown};
+use super::language::{self, LanguagePack};
+use super::lemma::{self, Lemmatizer};
+use super::move_history::{Move, MoveHistory, Victims};
+use super::set_bot::BotDifficulty;
+use super::steal_rules::{self, StealRule};
+use super::tile_bag::{self, BagPreset};
+use super::word_scoring::{ScoreBreakdown, ScoringSettings};
 use super::Uuid;
 use rand::seq::SliceRandom;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::router::Message;
+use crate::router::{DirectMessage, Message};
 
 use tokio::sync::broadcast;
 
 lazy_static! {
-    // Dictionary for anagrams (log(n) lookups)
-    static ref DICT: Arc<BTreeSet<String>> = {
-        let mut set: BTreeSet<String> = BTreeSet::new();
-        // Build path relative to this crate
-        let path = format!("{}/src/game/words.txt", env!("CARGO_MANIFEST_DIR"));
-        if let Ok(f) = std::fs::File::open(&path) {
-            let reader = std::io::BufReader::new(f);
-            for line in reader.lines().flatten() {
-                let word = line.trim().to_lowercase();
-                if word.is_empty() {
-                    continue;
-                }
-                set.insert(word);
-            }
-        }
-        Arc::new(set)
-    };
-
-
+    static ref LEMMATIZER: Box<dyn Lemmatizer> =
+        lemma::default_lemmatizer(dictionary::default_dictionary().words());
 }
 
-use pyo3::prelude::*;
-use pyo3::sync::PyOnceLock;
-use pyo3::types::PyDict;
-
-static NLP: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
-
-/// Initialize the spaCy model once. Returns true on success.
-pub fn init_lemmatizer() -> bool {
-    Python::attach(|py| {
-        // Add venv site-packages to sys.path if we're running from a venv
-        if let Ok(sys) = py.import("sys") {
-            if let Ok(path) = sys.getattr("path") {
-                // Try to import from venv
-                let venv_paths = vec![
-                    "./venv/lib/python3.11/site-packages",
-                    "./venv/lib/python3.10/site-packages",
-                    "./venv/lib/python3.12/site-packages",
-                    "venv/lib/python3.11/site-packages",
-                    "venv/lib/python3.10/site-packages",
-                    "venv/lib/python3.12/site-packages",
-                ];
-                for venv_path in venv_paths {
-                    let _ = path.call_method1("insert", (0, venv_path));
-                }
-            }
-        }
-
-        let spacy = match py.import("spacy") {
-            Ok(m) => m,
-            Err(_) => return false,
-        };
-        let kwargs = PyDict::new(py);
-        // exclude heavy pipeline components not needed
-        if kwargs.set_item("exclude", vec!["parser", "ner"]).is_err() {
-            return false;
-        }
-        let nlp_obj = match spacy.call_method("load", ("en_core_web_sm",), Some(&kwargs)) {
-            Ok(o) => o,
-            Err(_) => return false,
-        };
-        NLP.set(py, nlp_obj.unbind()).is_ok()
-    })
+/// Name of the lemmatizer backend in use; loads it on first call.
+pub fn lemmatizer_name() -> &'static str {
+    LEMMATIZER.name()
 }
 
-/// Returns true if the lemmas of word1 and word2 are equal; false on error.
+/// Returns true if word1 and word2 are forms of the same word (e.g. "run" and "ran").
 pub fn are_lemmas_equal(word1: &str, word2: &str) -> bool {
-    Python::attach(|py| {
-        if NLP.get(py).is_none() {
-            if !init_lemmatizer() {
-                return false;
-            }
-        }
-
-        let nlp_ref = match NLP.get(py) {
-            Some(n) => n,
-            None => return false,
-        };
-        let nlp = nlp_ref.bind(py);
-
-        // helper to get lemma of first token (convert to lowercase for consistency)
-        let get_lemma = |w: &str| -> Option<String> {
-            let lowercase_w = w.to_lowercase();
-            let doc = nlp.call_method1("__call__", (lowercase_w.as_str(),)).ok()?;
-            let tok0 = doc.get_item(0).ok()?;
-            tok0.getattr("lemma_").ok()?.extract::<String>().ok()
-        };
-
-        match (get_lemma(word1), get_lemma(word2)) {
-            (Some(l1), Some(l2)) => l1 == l2,
-            _ => false,
-        }
-    })
-}
-
-fn get_dict() -> Arc<BTreeSet<String>> {
-    DICT.clone()
+    LEMMATIZER.same_lemma(word1, word2)
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -142,146 +70,670 @@ impl PlayerBoard {
     }
 }
 
+/// Room settings chosen when an Anagrams game is created.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnagramsSettings {
+    /// House rules every steal has to pass
+    pub steal_rules: Vec<StealRule>,
+    /// Language pack code: tiles, letter normalisation and default word list
+    pub language: String,
+    /// Name of the word list plays are checked against; the language's own list if omitted
+    pub dictionary: Option<String>,
+    pub bag: BagPreset,
+    pub scoring: ScoringSettings,
+    pub endgame: EndgameSettings,
+    pub deal: DealMode,
+    /// Allow merging several words on the table (plus pot tiles) into one new word
+    pub combination_steals: bool,
+    pub challenge: ChallengeSettings,
+    pub hints: HintSettings,
+    /// Computer opponents seated when the room is created
+    pub bots: Vec<BotDifficulty>,
+}
+
+impl Default for AnagramsSettings {
+    fn default() -> Self {
+        AnagramsSettings {
+            steal_rules: vec![StealRule::NoSharedRoot],
+            language: language::DEFAULT_LANGUAGE.to_string(),
+            dictionary: None,
+            bag: BagPreset::default(),
+            scoring: ScoringSettings::default(),
+            endgame: EndgameSettings::default(),
+            deal: DealMode::default(),
+            combination_steals: false,
+            challenge: ChallengeSettings::default(),
+            hints: HintSettings::default(),
+            bots: Vec::new(),
+        }
+    }
+}
+
+impl AnagramsSettings {
+    /// Err if a room can't be created with these settings: a language other than English
+    /// whose word list isn't installed would otherwise be played against the English one.
+    pub fn check(&self) -> Result<(), String> {
+        let Some(pack) = language::get(&self.language) else {
+            return Ok(());
+        };
+        let list_name = self.dictionary.as_deref().unwrap_or(pack.dictionary);
+        if pack.code != language::DEFAULT_LANGUAGE && !dictionary::is_installed(list_name) {
+            return Err(format!(
+                "Word list \"{}\" for {} isn't installed.",
+                list_name, pack.name
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Anagrams {
     pub game_state: GameState,
+    pub settings: AnagramsSettings,
+    language: &'static LanguagePack,
+    dictionary: Dictionary,
+    /// Word list that settles challenges without a vote, if the room has one
+    referee: Option<Dictionary>,
+    /// When voting on the current challenge closes
+    challenge_deadline: Option<Instant>,
+    /// The state the current challenge interrupted ("in_progress" or "paused"), put back
+    /// when it's settled
+    interrupted_state: Option<String>,
+    /// What the bag started with, per tile
+    distribution: Vec<(String, u32)>,
+    /// Running once the bag is empty, if the room has a final countdown
+    countdown: Option<FinalCountdown>,
+    /// Players who have typed `/end`
+    end_votes: HashSet<Uuid>,
+    /// Index into the players of whoever flips next, for turn-based dealing
+    flip_turn: usize,
+    /// Players asking for a flip, for vote dealing
+    flip_votes: HashSet<Uuid>,
+    /// The best plays nobody made, filled in by the solver thread
+    missed: Arc<Mutex<MissedReport>>,
+    /// How many deals have been sent to the solver thread; each goes once the next tile
+    /// replaces it, so game end only has the last one left
+    deals_queued: usize,
+    /// Long solves run on the room's own thread, not under the game list's lock
+    solver_tx: mpsc::Sender<SolveJob>,
+    bots: Vec<AnagramsBot>,
+    /// The bots' choice of plays, from the solver thread; the table only changes every few
+    /// seconds, so one solve serves every bot until it does
+    bot_plays: Arc<Mutex<BotPlays>>,
+    /// The table version the bots' plays were last asked for
+    bot_solve_asked: Option<u64>,
     inner: Arc<RwLock<Inner>>,
 }
 
-#[derive(Debug, Clone)]
-struct LastMove {
-    attacker_id: Uuid,
-    victim_id: Option<Uuid>, // None if word was taken from pot
-    word_taken: String,
-    word_stolen: Option<String>, // None if taken from pot
-    old_pot: Vec<char>,
+/// Work for a room's solver thread.
+#[derive(Debug)]
+enum SolveJob {
+    /// Deals the next tile has replaced, for the missed-plays report; `last` once the game
+    /// is over and nothing more will be dealt
+    Missed {
+        deals: Vec<DealSnapshot>,
+        last: bool,
+    },
+    /// The longest `limit` plays on the table at `version`, for the bots
+    Bots {
+        version: u64,
+        pot: Vec<String>,
+        table: Vec<(Uuid, String)>,
+        limit: usize,
+    },
+}
+
+/// The bots' choice of plays and the table version it was solved for.
+type BotPlays = Option<(u64, Arc<Vec<Play>>)>;
+
+/// The missed-plays report, built up a deal at a time.
+#[derive(Debug, Default)]
+struct MissedReport {
+    deals: Vec<MissedDeal>,
+    /// Every deal of a finished game has been solved
+    complete: bool,
+}
+
+/// Start the solver thread for a room. It works through jobs in order and stops once the
+/// game, and with it the sender, is dropped. When the report is complete it broadcasts it as
+/// `missed_plays`.
+fn spawn_solver(
+    language: &'static LanguagePack,
+    dictionary: Dictionary,
+    steal_rules: Vec<StealRule>,
+    missed: Arc<Mutex<MissedReport>>,
+    bot_plays: Arc<Mutex<BotPlays>>,
+    broadcast_tx: Arc<broadcast::Sender<String>>,
+) -> mpsc::Sender<SolveJob> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let steal_allowed =
+            |old: &str, new: &str| steal_rules::check_all(&steal_rules, old, new).is_ok();
+        for job in rx {
+            // the word list's index is built on first use, which is slow; better here
+            let solver = anagrams_solver::solver_for(language, &dictionary);
+            match job {
+                SolveJob::Missed { deals, last } => {
+                    for deal in &deals {
+                        if let Some(missed_deal) = solver.missed_play(deal, steal_allowed) {
+                            missed.lock().unwrap().deals.push(missed_deal);
+                        }
+                    }
+                    if last {
+                        let mut report = missed.lock().unwrap();
+                        report.complete = true;
+                        let msg = Message {
+                            kind: "missed_plays".into(),
+                            data: serde_json::to_string(&report.deals).unwrap(),
+                        };
+                        let _ = broadcast_tx.send(serde_json::to_string(&msg).unwrap());
+                    }
+                }
+                SolveJob::Bots {
+                    version,
+                    pot,
+                    table,
+                    limit,
+                } => {
+                    let plays = solver.solve(&pot, &table, steal_allowed, limit);
+                    *bot_plays.lock().unwrap() = Some((version, Arc::new(plays)));
+                }
+            }
+        }
+    });
+    tx
 }
 
+/// A word on the table a player names as the one they're stealing.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StealTarget {
+    pub player_id: Uuid,
+    pub word: String,
+}
+
+/// Most words a combination steal may merge.
+const MAX_COMBINED_WORDS: usize = 3;
+
 #[derive(Debug, Clone)]
 struct Inner {
     bag: Bag,
-    pot: Vec<char>,
+    pot: Vec<String>,
     players_boards: Vec<PlayerBoard>,
     paused: bool,
     active_challenge: bool,
     challenge_votes: HashMap<Uuid, bool>, // true = challenge, false = maintain
-    last_move: Option<LastMove>,
+    /// Recent moves, open to challenge
+    history: MoveHistory,
+    /// The move the current challenge is about
+    challenged_move: Option<u64>,
+    /// How many of each player's words have been stolen, for the steal penalty
+    stolen_from: HashMap<Uuid, u32>,
+    /// The table after each tile dealt, for the missed-plays report
+    deals: Vec<DealSnapshot>,
+    hints_used: HashMap<Uuid, u32>,
+    /// Bumped whenever the pot or the words on the table change, for the bots
+    version: u64,
 }
 
 #[derive(Debug, Serialize)]
 struct AnagramCompletedData {
     game_state: GameState,
-    pot: Vec<char>,
+    pot: Vec<String>,
     players_boards: Vec<PlayerBoard>,
     chat: Vec<super::ChatMessage>,
+    moves: Vec<Move>,
+    /// The move just made
+    description: MoveDescription,
+}
+
+/// A word taken, spelled out for clients.
+#[derive(Debug, Clone, Serialize)]
+struct MoveDescription {
+    move_id: u64,
+    attacker_id: Uuid,
+    attacker: String,
+    word: String,
+    /// Empty if the word came from the pot alone
+    stolen: Vec<StolenWord>,
+    pot_tiles: Vec<String>,
+    /// e.g. "Alice took treat from Bob's eat, adding r and t!"
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StolenWord {
+    player_id: Uuid,
+    player: String,
+    word: String,
 }
 
 #[derive(Debug, Serialize)]
 struct GameStateData {
     game_state: GameState,
-    pot: Vec<char>,
+    pot: Vec<String>,
     players_boards: Vec<PlayerBoard>,
     chat: Vec<super::ChatMessage>,
+    moves: Vec<Move>,
+    settings: AnagramsSettings,
+    /// Whose turn it is to flip, for turn-based dealing
+    flipper: Option<Uuid>,
+    /// Per-word scoring, sent once the game is over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scores: Option<Vec<ScoreBreakdown>>,
+    /// The best plays nobody made after each tile, once the game is over and they are solved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    missed: Option<Vec<MissedDeal>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FlipTurnData {
+    player_id: Uuid,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EndgameClockData {
+    remaining_ms: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
 struct Bag {
-    letters: Vec<char>,
+    letters: Vec<String>,
 }
 
 impl Bag {
-    fn new() -> Self {
-        // Placeholder for dealing cards logic
-        let mut letters: Vec<char> = Vec::with_capacity(200);
-        let _ = "AAAAAAAAAAAAABBBCCCDDDDDDEEEEEEEEEEEEEEEEEEFFFGGGGHHHIIIIIIIIIIIJJKKLLLLLMMMNNNNNNNNOOOOOOOOOOOPPPQQRRRRRRRRRSSSSSSTTTTTTTTTUUUUUUVVVWWWXXYYYZZ"
-            .to_lowercase()
-            .chars()
-            .for_each(|x| letters.push(x));
+    fn new(distribution: &[(String, u32)]) -> Self {
+        let mut letters: Vec<String> = distribution
+            .iter()
+            .flat_map(|(tile, count)| std::iter::repeat_n(tile.clone(), *count as usize))
+            .collect();
         // use a thread-local RNG for shuffling
-        let mut rng = rand::rng();
-        letters.shuffle(&mut rng);
+        letters.shuffle(&mut rand::rng());
         Bag { letters }
     }
+
+    fn remaining(&self, tile: &str) -> u32 {
+        self.letters.iter().filter(|t| *t == tile).count() as u32
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TileCount {
+    tile: String,
+    remaining: u32,
+    total: u32,
 }
 
-fn pot_anagram(word_to_check: &str, mut pot: Vec<char>) -> Option<Vec<char>> {
-    let mut counter: HashMap<char, i8> = HashMap::new();
+#[derive(Debug, Serialize)]
+struct BagCountsData {
+    remaining: usize,
+    tiles: Vec<TileCount>,
+}
 
-    for ch in word_to_check.chars() {
-        *counter.entry(ch).or_insert(0) += 1;
+/// How many of each tile a word uses.
+fn tile_counts(tiles: &[String]) -> HashMap<&str, usize> {
+    let mut counter: HashMap<&str, usize> = HashMap::new();
+    for tile in tiles {
+        *counter.entry(tile.as_str()).or_insert(0) += 1;
     }
+    counter
+}
 
-    // consume letters from the pot; if any required letter is missing, return None
-    for (ch, count) in counter.into_iter() {
-        for _ in 0..count {
-            if let Some(pos) = pot.iter().position(|c| *c == ch) {
-                pot.remove(pos);
-            } else {
-                return None;
-            }
+/// Take `counter`'s tiles out of the pot; None if the pot runs short.
+fn take_from_pot(counter: &HashMap<&str, usize>, mut pot: Vec<String>) -> Option<Vec<String>> {
+    for (tile, count) in counter {
+        for _ in 0..*count {
+            let pos = pot.iter().position(|t| t == tile)?;
+            pot.remove(pos);
         }
     }
-
     Some(pot)
 }
 
-fn some_anagram(existing_word: &str, word_to_check: &str, mut pot: Vec<char>) -> Option<Vec<char>> {
-    let mut counter: HashMap<char, i8> = HashMap::new();
+fn pot_anagram(word_to_check: &[String], pot: Vec<String>) -> Option<Vec<String>> {
+    take_from_pot(&tile_counts(word_to_check), pot)
+}
+
+fn some_anagram(
+    existing_word: &[String],
+    word_to_check: &[String],
+    pot: Vec<String>,
+) -> Option<Vec<String>> {
     if word_to_check.len() <= existing_word.len() {
         return None;
     }
 
-    if are_lemmas_equal(existing_word, word_to_check) {
-        return None;
+    // every tile of the existing word must be reused; the rest comes from the pot
+    let mut counter = tile_counts(word_to_check);
+    for tile in existing_word {
+        let amount = counter.get_mut(tile.as_str())?;
+        if *amount == 0 {
+            return None;
+        }
+        *amount -= 1;
     }
 
-    for letter in word_to_check.chars() {
-        *counter.entry(letter).or_insert(0) += 1;
-    }
+    take_from_pot(&counter, pot)
+}
 
-    for letter in existing_word.chars() {
-        match counter.get(&letter) {
-            Some(amount) => {
-                if *amount == 0 {
-                    return None;
-                } else {
-                    counter.insert(letter, amount - 1)
-                }
+/// Every way to make `word_to_check` from whole words in `table` plus at least one pot tile,
+/// using at most `max_words` words. `own[i]` is whether table word `i` belongs to the player
+/// stealing. Each steal is the indices of the words used and the pot left afterwards; the best
+/// comes first: the most tiles taken from the table (so the fewest from the pot), then the
+/// fewest words, then other players' words before the player's own, then the words earliest
+/// on the table.
+fn find_steals(
+    table: &[Vec<String>],
+    own: &[bool],
+    word_to_check: &[String],
+    pot: &[String],
+    max_words: usize,
+) -> Vec<(Vec<usize>, Vec<String>)> {
+    fn search(
+        table: &[Vec<String>],
+        start: usize,
+        remaining: &mut HashMap<&str, usize>,
+        chosen: &mut Vec<usize>,
+        max_words: usize,
+        found: &mut Vec<Vec<usize>>,
+    ) {
+        if !chosen.is_empty() {
+            found.push(chosen.clone());
+        }
+        if chosen.len() == max_words {
+            return;
+        }
+        for i in start..table.len() {
+            // only words whose tiles all still fit in the new word can join the merge
+            let fits = tile_counts(&table[i])
+                .iter()
+                .all(|(tile, n)| remaining.get(tile).is_some_and(|left| left >= n));
+            if table[i].is_empty() || !fits {
+                continue;
             }
-            None => return None,
-        };
+            for tile in &table[i] {
+                *remaining.get_mut(tile.as_str()).unwrap() -= 1;
+            }
+            chosen.push(i);
+            search(table, i + 1, remaining, chosen, max_words, found);
+            chosen.pop();
+            for tile in &table[i] {
+                *remaining.get_mut(tile.as_str()).unwrap() += 1;
+            }
+        }
     }
 
-    // Check if we can consume the required letters from the pot
-    for (ch, count) in counter.iter() {
-        let mut needed = *count;
-        while needed > 0 {
-            if let Some(pos) = pot.iter().position(|c| c == ch) {
-                pot.remove(pos);
-                needed -= 1;
-            } else {
-                // Not enough of this letter in the pot
-                return None;
-            }
+    let mut combinations = Vec::new();
+    search(
+        table,
+        0,
+        &mut tile_counts(word_to_check),
+        &mut Vec::new(),
+        max_words,
+        &mut combinations,
+    );
+    let mut steals: Vec<(Vec<usize>, Vec<String>)> = combinations
+        .into_iter()
+        .filter_map(|words| {
+            let existing: Vec<String> = words.iter().flat_map(|&i| table[i].clone()).collect();
+            let new_pot = some_anagram(&existing, word_to_check, pot.to_vec())?;
+            Some((words, new_pot))
+        })
+        .collect();
+    let own_count = |words: &[usize]| words.iter().filter(|&&i| own[i]).count();
+    steals.sort_by(|(a_words, a_pot), (b_words, b_pot)| {
+        b_pot
+            .len()
+            .cmp(&a_pot.len())
+            .then(a_words.len().cmp(&b_words.len()))
+            .then(own_count(a_words).cmp(&own_count(b_words)))
+            .then(a_words.cmp(b_words))
+    });
+    steals
+}
+
+/// Players whose votes count on a challenge: everyone connected except whoever made the
+/// challenged move.
+fn challenge_voters(inner: &Inner) -> Vec<Uuid> {
+    let challenged = inner
+        .challenged_move
+        .and_then(|id| inner.history.get(id))
+        .map(|m| m.attacker_id);
+    inner
+        .players_boards
+        .iter()
+        .map(|pb| &pb.player)
+        .filter(|p| p.is_connected() && Some(p.id) != challenged)
+        .map(|p| p.id)
+        .collect()
+}
+
+/// A referee word list's ruling on a move: Ok if it stands, Err if it's reverted, each with
+/// the reason. Shared roots are only checked in languages the lemmatizer knows.
+fn referee_verdict(
+    referee: &Dictionary,
+    language: &LanguagePack,
+    challenged: &Move,
+) -> Result<String, String> {
+    let word = &challenged.word_taken;
+    if !referee.contains(word) {
+        return Err(format!("{} isn't in the {} word list.", word, referee.name));
+    }
+    if let Some((_, stolen)) = challenged
+        .victims
+        .iter()
+        .find(|(_, stolen)| language.has_roots() && are_lemmas_equal(stolen, word))
+    {
+        return Err(format!("{} shares a root with {}.", word, stolen));
+    }
+    Ok(format!("{} is in the {} word list.", word, referee.name))
+}
+
+fn player_name(boards: &[PlayerBoard], id: &Uuid) -> String {
+    boards
+        .iter()
+        .find(|pb| pb.player.id == *id)
+        .map(|pb| pb.player.name.clone())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn describe_move(made: &Move, boards: &[PlayerBoard]) -> MoveDescription {
+    let attacker = player_name(boards, &made.attacker_id);
+    let stolen: Vec<StolenWord> = made
+        .victims
+        .iter()
+        .map(|(player_id, word)| StolenWord {
+            player_id: *player_id,
+            player: player_name(boards, player_id),
+            word: word.clone(),
+        })
+        .collect();
+    let text = if stolen.is_empty() {
+        format!("{} formed {} from the pot!", attacker, made.word_taken)
+    } else {
+        let sources: Vec<String> = stolen
+            .iter()
+            .map(|s| format!("{}'s {}", s.player, s.word))
+            .collect();
+        format!(
+            "{} took {} from {}, adding {}!",
+            attacker,
+            made.word_taken,
+            sources.join(" and "),
+            made.pot_tiles.join(" and ")
+        )
+    };
+    MoveDescription {
+        move_id: made.id,
+        attacker_id: made.attacker_id,
+        attacker,
+        word: made.word_taken.clone(),
+        stolen,
+        pot_tiles: made.pot_tiles.clone(),
+        text,
+    }
+}
+
+/// The tiles a move took out of the pot, given the pot before and after.
+fn pot_tiles_used(old_pot: &[String], new_pot: &[String]) -> Vec<String> {
+    take_from_pot(&tile_counts(new_pot), old_pot.to_vec()).unwrap_or_default()
+}
+
+/// Undo one move: the word leaves the attacker, the victims get theirs back and the pot
+/// tiles return to the pot.
+fn revert_move(inner: &mut Inner, undone: &Move) {
+    if let Some(attacker_board) = inner
+        .players_boards
+        .iter_mut()
+        .find(|pb| pb.player.id == undone.attacker_id)
+    {
+        attacker_board.remove_word(&undone.word_taken);
+    }
+    for (victim_id, stolen_word) in &undone.victims {
+        if let Some(victim_board) = inner
+            .players_boards
+            .iter_mut()
+            .find(|pb| pb.player.id == *victim_id)
+        {
+            victim_board.add_word(stolen_word.clone());
+        }
+        if let Some(stolen) = inner.stolen_from.get_mut(victim_id) {
+            *stolen = stolen.saturating_sub(1);
         }
     }
+    inner.pot.extend(undone.pot_tiles.iter().cloned());
+}
 
-    Some(pot)
+/// Move the next tile from the bag to the pot and broadcast the new pot; false if the bag is empty.
+fn deal_tile(inner: &mut Inner, broadcast_tx: &broadcast::Sender<String>) -> bool {
+    let Some(tile) = inner.bag.letters.pop() else {
+        return false;
+    };
+    inner.pot.push(tile);
+    inner.version += 1;
+    inner.deals.push(DealSnapshot {
+        tile_number: inner.deals.len() + 1,
+        pot: inner.pot.clone(),
+        table: inner
+            .players_boards
+            .iter()
+            .flat_map(|board| {
+                board
+                    .words
+                    .iter()
+                    .map(|word| (board.player.id, word.clone()))
+            })
+            .collect(),
+        taken: Vec::new(),
+    });
+    let msg = Message {
+        kind: "new_tile".into(),
+        data: serde_json::to_string(&inner.pot).unwrap(),
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    let _ = broadcast_tx.send(json);
+    true
 }
 
-impl Anagrams {
-    pub fn new(name: String) -> Self {
-        let mut bag = Bag::new();
-        let pot = vec![bag.letters.pop().unwrap()];
+/// Copy each breakdown's total onto the matching player; negative totals count as zero.
+fn apply_scores(players: &mut [Player], scores: &[ScoreBreakdown]) {
+    for score in scores {
+        if let Some(player) = players.iter_mut().find(|p| p.id == score.player_id) {
+            player.score = score.total.max(0) as u32;
+        }
+    }
+}
 
-        let game_state = GameState {
+impl Anagrams {
+    pub fn new(name: String, mut settings: AnagramsSettings) -> Self {
+        let mut game_state = GameState {
             id: Uuid::new_v4(),
             name,
             broadcast_tx: Arc::new(tokio::sync::broadcast::channel(64).0),
             players: vec![],
             current_state: String::from("in_progress"),
             chat: Vec::new(),
+            listed: true,
         };
+        let language = language::get(&settings.language).unwrap_or_else(|| {
+            game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!(
+                    "Unknown language \"{}\"; playing in English.",
+                    settings.language
+                ),
+                cards: None,
+                message_type: Some("error".to_string()),
+            });
+            language::default_pack()
+        });
+        settings.language = language.code.to_string();
+        if !language.has_roots() {
+            settings
+                .steal_rules
+                .retain(|rule| *rule != StealRule::NoSharedRoot);
+        }
+
+        let list_name = settings
+            .dictionary
+            .clone()
+            .unwrap_or_else(|| language.dictionary.to_string());
+        let dictionary = dictionary::get(&list_name).unwrap_or_else(|| {
+            game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!(
+                    "Word list \"{}\" isn't installed; using {}.",
+                    list_name,
+                    dictionary::DEFAULT_DICTIONARY
+                ),
+                cards: None,
+                message_type: Some("error".to_string()),
+            });
+            dictionary::default_dictionary()
+        });
+        settings.dictionary = Some(dictionary.name.clone());
+        let dictionary = language.normalized_dictionary(&dictionary);
+
+        let referee = settings.challenge.referee.clone().and_then(|list_name| {
+            let referee = dictionary::get(&list_name);
+            if referee.is_none() {
+                game_state.chat.push(super::ChatMessage {
+                    sender: "System".to_string(),
+                    text: format!(
+                        "Word list \"{}\" isn't installed; challenges will be put to a vote.",
+                        list_name
+                    ),
+                    cards: None,
+                    message_type: Some("error".to_string()),
+                });
+                settings.challenge.referee = None;
+            }
+            referee.map(|r| language.normalized_dictionary(&r))
+        });
+
+        let distribution = tile_bag::distribution(language, &settings.bag).unwrap_or_else(|e| {
+            game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!("{} Using the standard bag.", e),
+                cards: None,
+                message_type: Some("error".to_string()),
+            });
+            settings.bag = BagPreset::Standard;
+            language.distribution()
+        });
+        let mut bag = Bag::new(&distribution);
+        let pot = vec![bag.letters.pop().unwrap()];
+        if !settings.steal_rules.is_empty() {
+            let rules: Vec<&str> = settings.steal_rules.iter().map(|r| r.name()).collect();
+            game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!("House rules for steals: {}.", rules.join(", ")),
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+        }
 
         let inner = Arc::new(RwLock::new(Inner {
             bag,
@@ -290,121 +742,361 @@ impl Anagrams {
             paused: false,
             active_challenge: false,
             challenge_votes: HashMap::new(),
-            last_move: None,
+            history: MoveHistory::default(),
+            challenged_move: None,
+            stolen_from: HashMap::new(),
+            deals: Vec::new(),
+            hints_used: HashMap::new(),
+            version: 0,
         }));
 
-        // spawn a background tile dealer thread that owns a clone of inner and the broadcast tx
-        let inner_clone = inner.clone();
-        let broadcast_clone = game_state.broadcast_tx.clone();
-        std::thread::spawn(move || {
-            // We check pause more often than the deal interval so pausing is responsive.
-            let deal_interval = std::time::Duration::from_secs(7);
-            let tick = std::time::Duration::from_millis(200);
-            let mut accumulated = std::time::Duration::ZERO;
-            loop {
-                std::thread::sleep(tick);
-
-                // If paused, don't advance accumulated time or deal tiles
-                {
-                    let inner_r = inner_clone.read().unwrap();
-                    if inner_r.paused {
+        if settings.deal != DealMode::default() {
+            game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: settings.deal.describe(),
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+        }
+
+        // in timer mode, spawn a background tile dealer thread that owns a clone of inner and the broadcast tx
+        if let Some(deal_interval) = settings.deal.interval() {
+            let inner_clone = inner.clone();
+            let broadcast_clone = game_state.broadcast_tx.clone();
+            std::thread::spawn(move || {
+                // We check pause more often than the deal interval so pausing is responsive.
+                let tick = std::time::Duration::from_millis(200);
+                let mut accumulated = std::time::Duration::ZERO;
+                loop {
+                    std::thread::sleep(tick);
+
+                    // If paused, don't advance accumulated time or deal tiles
+                    {
+                        let inner_r = inner_clone.read().unwrap();
+                        if inner_r.paused {
+                            continue;
+                        }
+                    }
+
+                    accumulated += tick;
+                    if accumulated < deal_interval {
                         continue;
                     }
+                    accumulated = std::time::Duration::ZERO;
+
+                    let mut inner_w = inner_clone.write().unwrap();
+                    if !deal_tile(&mut inner_w, &broadcast_clone) {
+                        // Broadcast a system chat message to clients notifying there are no more tiles
+                        let msg = Message {
+                            kind: "chat".into(),
+                            data: r#"{"sender":"System","message":"No more tiles remaining.","message_type":"info"}"#.to_string(),
+                        };
+                        let json = serde_json::to_string(&msg).unwrap();
+                        let _ = broadcast_clone.send(json);
+                        break;
+                    }
                 }
+            });
+        }
 
-                accumulated += tick;
-                if accumulated < deal_interval {
-                    continue;
-                }
-                accumulated = std::time::Duration::ZERO;
+        let missed = Arc::new(Mutex::new(MissedReport::default()));
+        let bot_plays = Arc::new(Mutex::new(None));
+        let solver_tx = spawn_solver(
+            language,
+            dictionary.clone(),
+            settings.steal_rules.clone(),
+            missed.clone(),
+            bot_plays.clone(),
+            game_state.broadcast_tx.clone(),
+        );
 
-                let mut inner_w = inner_clone.write().unwrap();
-                if let Some(tile) = inner_w.bag.letters.pop() {
-                    inner_w.pot.push(tile);
-                    let msg = Message {
-                        kind: "new_tile".into(),
-                        data: serde_json::to_string(&inner_w.pot).unwrap(),
-                    };
-                    let json = serde_json::to_string(&msg).unwrap();
-                    let _ = broadcast_clone.send(json);
-                } else {
-                    // Broadcast a system chat message to clients notifying there are no more tiles
-                    let msg = Message {
-                        kind: "chat".into(),
-                        data: r#"{"sender":"System","message":"No more tiles remaining.","message_type":"info"}"#.to_string(),
-                    };
-                    let json = serde_json::to_string(&msg).unwrap();
-                    let _ = broadcast_clone.send(json);
-                    break;
+        let mut game = Self {
+            game_state,
+            settings,
+            language,
+            dictionary,
+            referee,
+            challenge_deadline: None,
+            interrupted_state: None,
+            distribution,
+            countdown: None,
+            end_votes: HashSet::new(),
+            flip_turn: 0,
+            flip_votes: HashSet::new(),
+            missed,
+            deals_queued: 0,
+            solver_tx,
+            bots: Vec::new(),
+            bot_plays,
+            bot_solve_asked: None,
+            inner,
+        };
+        for difficulty in game.settings.bots.clone() {
+            game.add_bot(difficulty);
+        }
+        game
+    }
+
+    /// Seat a bot as a new player with its own board and return its id.
+    fn add_bot(&mut self, difficulty: BotDifficulty) -> Uuid {
+        let base = format!("{} Bot", difficulty.label());
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.game_state.players.iter().any(|p| p.name == name) {
+            n += 1;
+            name = format!("{} {}", base, n);
+        }
+        let player = Player::bot(name);
+        let id = player.id;
+        self.inner
+            .write()
+            .unwrap()
+            .players_boards
+            .push(PlayerBoard::from_player(&player));
+        self.game_state.players.push(player);
+        self.bots.push(AnagramsBot::new(id, difficulty));
+        // it may look through more plays than the bots already seated
+        self.bot_solve_asked = None;
+        id
+    }
+
+    /// Bots vote on an open challenge; otherwise the first bot done looking at the table
+    /// makes its move.
+    fn tick_bots(&mut self) {
+        if self.bots.is_empty() {
+            return;
+        }
+        let (paused, active_challenge, challenged, voted, version) = {
+            let inner_r = self.inner.read().unwrap();
+            (
+                inner_r.paused,
+                inner_r.active_challenge,
+                inner_r
+                    .challenged_move
+                    .and_then(|id| inner_r.history.get(id))
+                    .cloned(),
+                inner_r
+                    .challenge_votes
+                    .keys()
+                    .copied()
+                    .collect::<Vec<Uuid>>(),
+                inner_r.version,
+            )
+        };
+        if active_challenge {
+            // a bot keeps the move if the word is listed and shares no root with what it stole
+            if let Some(challenged) = challenged {
+                let revert = referee_verdict(&self.dictionary, self.language, &challenged).is_err();
+                let voters: Vec<Uuid> = self
+                    .bots
+                    .iter()
+                    .map(|bot| bot.player_id)
+                    .filter(|id| !voted.contains(id) && *id != challenged.attacker_id)
+                    .collect();
+                for id in voters {
+                    let _ = self.vote_challenge(&id, revert);
                 }
             }
-        });
+            return;
+        }
+        if paused {
+            return;
+        }
 
-        Self { game_state, inner }
+        for bot in self.bots.iter_mut() {
+            bot.observe(version);
+        }
+        // solve a changed table straight away, so the plays are ready once a bot is
+        if self.bot_solve_asked != Some(version) {
+            let (pot, table) = {
+                let inner_r = self.inner.read().unwrap();
+                let table: Vec<(Uuid, String)> = inner_r
+                    .players_boards
+                    .iter()
+                    .flat_map(|board| {
+                        board
+                            .words
+                            .iter()
+                            .map(|word| (board.player.id, word.clone()))
+                    })
+                    .collect();
+                (inner_r.pot.clone(), table)
+            };
+            let limit = self.bots.iter().map(|bot| bot.plays_wanted()).max();
+            let _ = self.solver_tx.send(SolveJob::Bots {
+                version,
+                pot,
+                table,
+                limit: limit.unwrap_or_default(),
+            });
+            self.bot_solve_asked = Some(version);
+        }
+        let Some(index) = self.bots.iter().position(|bot| bot.is_ready()) else {
+            return;
+        };
+        // the table can change between a solve and the next tick; wait for the new one
+        let plays = match &*self.bot_plays.lock().unwrap() {
+            Some((solved, plays)) if *solved == version => plays.clone(),
+            _ => return,
+        };
+        let bag_empty = self.inner.read().unwrap().bag.letters.is_empty();
+        let bot_id = self.bots[index].player_id;
+        match self.bots[index].choose(&plays) {
+            Some(play) => {
+                let targets: Vec<StealTarget> = match (play.victim, play.stolen_word) {
+                    (Some(player_id), Some(word)) => vec![StealTarget { player_id, word }],
+                    _ => Vec::new(),
+                };
+                let _ = self.anagram_attempt(play.word, bot_id, &targets);
+            }
+            // nothing it wants: ask for the next tile, or to finish once the bag is empty
+            None if bag_empty => {
+                if self.settings.endgame.vote_to_end {
+                    let _ = self.vote_to_end(&bot_id);
+                }
+            }
+            None => {
+                if self.settings.deal.interval().is_none() {
+                    let _ = self.flip(&bot_id);
+                }
+            }
+        }
     }
 
     // synchronous version of anagram attempt
-    fn anagram_attempt(&mut self, word_to_check: String, player_id: Uuid) -> Result<(), &str> {
+    /// Take `word_to_check` for `player_id`. With `targets`, only that exact steal is tried;
+    /// otherwise the best steal by the default priority is made, or the word is built from the pot.
+    fn anagram_attempt(
+        &mut self,
+        word_to_check: String,
+        player_id: Uuid,
+        targets: &[StealTarget],
+    ) -> Result<(), String> {
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.".into());
+        }
         // Don't allow attempts while paused or during a challenge
         {
             let inner_r = self.inner.read().unwrap();
             if inner_r.paused {
-                return Err("Game is paused.");
+                return Err("Game is paused.".into());
             }
             if inner_r.active_challenge {
-                return Err("Cannot take words during a challenge.");
+                return Err("Cannot take words during a challenge.".into());
             }
         }
 
-        // Validate the attempted word exists in our dictionary (log n lookup via BTreeSet)
-
-        if word_to_check.trim().len() < 3 {
-            return Err("Word must be at least 3 characters long.");
+        let word_to_check = self.language.normalize(&word_to_check);
+        let Some(word_tiles) = self.language.tiles(&word_to_check) else {
+            return Err(format!(
+                "{} can't be spelled with this game's tiles.",
+                word_to_check
+            ));
+        };
+        if word_tiles.len() < 3 {
+            return Err("Word must be at least 3 letters long.".into());
         }
 
-        let dict = get_dict();
-        let word_to_check = word_to_check.trim().to_lowercase();
-        if !dict.contains(&word_to_check) {
-            return Err("Word not in dictionary.");
+        // Validate the attempted word exists in our dictionary (log n lookup via BTreeSet)
+        if !self.dictionary.contains(&word_to_check) {
+            return Err("Word not in dictionary.".into());
         }
 
         // If there are no other player boards, nothing to steal from
-        // Acquire read lock and search for a victim; collect necessary data then drop the read lock before mutating
-        let mut found: Option<(Vec<char>, usize, Uuid)> = None;
-        let mut found_pot: Option<Vec<char>> = None;
+        // Acquire read lock and search for victims; collect necessary data then drop the read lock before mutating
+        let mut found: Option<(Vec<String>, Victims)> = None;
+        let found_pot: Option<Vec<String>>;
+        // why the first steal the letters allowed was refused by the room's rules
+        let mut rejection: Option<String> = None;
         {
             let inner_r = self.inner.read().unwrap();
             if inner_r.players_boards.is_empty() {
-                return Err("No players to take from.");
+                return Err("No players to take from.".into());
             }
 
-            // Build a list of player indices and shuffle for random order
-            let mut random_player_order: Vec<usize> = (0..inner_r.players_boards.len()).collect();
-            random_player_order.shuffle(&mut rand::rng());
+            // every word on the table with its owner, in board order
+            let table: Vec<(Uuid, &String)> = inner_r
+                .players_boards
+                .iter()
+                .flat_map(|board| board.words.iter().map(|word| (board.player.id, word)))
+                .collect();
+            let table_tiles: Vec<Vec<String>> = table
+                .iter()
+                .map(|(_, word)| self.language.tiles(word).unwrap_or_default())
+                .collect();
+            let max_words = if self.settings.combination_steals {
+                MAX_COMBINED_WORDS
+            } else {
+                1
+            };
 
-            for player_i in random_player_order {
-                if let Some(player_board) = inner_r.players_boards.get(player_i) {
-                    for (word_i, existing_word) in player_board.words.iter().enumerate() {
-                        if let Some(new_pot) =
-                            some_anagram(existing_word, &word_to_check, inner_r.pot.clone())
-                        {
-                            // capture the necessary data and break out
-                            found = Some((new_pot, word_i, player_board.player.id.clone()));
-                            break;
-                        }
-                    }
+            // the table words a targeted attempt names, as sorted indices into `table`
+            let mut targeted: Vec<usize> = Vec::new();
+            let mut target_names: Vec<String> = Vec::new();
+            if targets.len() > max_words {
+                return Err(if max_words == 1 {
+                    "This room doesn't allow combining words.".to_string()
+                } else {
+                    format!("At most {} words can be combined.", max_words)
+                });
+            }
+            for target in targets {
+                let word = self.language.normalize(&target.word);
+                let owner = inner_r
+                    .players_boards
+                    .iter()
+                    .find(|pb| pb.player.id == target.player_id)
+                    .map(|pb| pb.player.name.clone())
+                    .unwrap_or_else(|| "That player".to_string());
+                let Some(i) = (0..table.len()).find(|i| {
+                    table[*i].0 == target.player_id && *table[*i].1 == word && !targeted.contains(i)
+                }) else {
+                    return Err(format!("{} doesn't have {}.", owner, word));
+                };
+                targeted.push(i);
+                target_names.push(format!("{}'s {}", owner, word));
+            }
+            targeted.sort_unstable();
+
+            let own: Vec<bool> = table.iter().map(|(owner, _)| *owner == player_id).collect();
+            let steals = find_steals(&table_tiles, &own, &word_tiles, &inner_r.pot, max_words);
+
+            for (words, new_pot) in steals {
+                if !targeted.is_empty() && words != targeted {
+                    continue;
                 }
-                if found.is_some() {
-                    break;
+                let refused = words.iter().find_map(|&i| {
+                    steal_rules::check_all(&self.settings.steal_rules, table[i].1, &word_to_check)
+                        .err()
+                });
+                if let Some(reason) = refused {
+                    rejection.get_or_insert(reason);
+                    continue;
                 }
+                let victims = words
+                    .iter()
+                    .map(|&i| (table[i].0, table[i].1.clone()))
+                    .collect();
+                found = Some((new_pot, victims));
+                break;
+            }
+
+            if !targeted.is_empty() && found.is_none() {
+                return Err(rejection.unwrap_or_else(|| {
+                    format!(
+                        "{} can't be made from {} and the pot.",
+                        word_to_check,
+                        target_names.join(" and ")
+                    )
+                }));
             }
 
-            found_pot = pot_anagram(&word_to_check, inner_r.pot.clone());
+            found_pot = pot_anagram(&word_tiles, inner_r.pot.clone());
         }
 
-        if let Some((new_pot, word_i, victim_id)) = found {
+        if let Some((new_pot, victims)) = found {
             // perform mutation under write lock
-            self.sync_state(new_pot, &word_to_check, word_i, &player_id, &victim_id);
+            self.sync_state(new_pot, &word_to_check, &victims, &player_id);
             return Ok(());
         }
 
@@ -414,10 +1106,10 @@ impl Anagrams {
             return Ok(());
         }
 
-        Err("That word cannot be taken.")
+        Err(rejection.unwrap_or_else(|| "That word cannot be taken.".into()))
     }
 
-    fn pot_state_sync(&mut self, new_pot: Vec<char>, new_word: &str, player_id: &Uuid) {
+    fn pot_state_sync(&mut self, new_pot: Vec<String>, new_word: &str, player_id: &Uuid) {
         // perform modifications under write lock
         let mut inner_w = self.inner.write().unwrap();
 
@@ -427,39 +1119,36 @@ impl Anagrams {
             .position(|x| x.player.id == *player_id)
             .unwrap();
 
-        let player_name = inner_w.players_boards[player_index].player.name.clone();
-
-        let _chat_message_text = format!("{} formed {} from the pot!", player_name, new_word);
-
-        // self.game_state.chat.push(super::ChatMessage {
-        //     sender: "System".to_string(),
-        //     text: chat_message_text,
-        //     cards: None,
-        //     message_type: Some("success".to_string()),
-        // });
-
-        // Record this move before changing state
-        let old_pot = inner_w.pot.clone();
+        // Record this move for potential challenge before changing state
+        let pot_tiles = pot_tiles_used(&inner_w.pot, &new_pot);
+        let move_id = inner_w
+            .history
+            .record(*player_id, new_word, Vec::new(), pot_tiles);
+        let description = describe_move(
+            inner_w.history.get(move_id).unwrap(),
+            &inner_w.players_boards,
+        );
 
         inner_w.pot = new_pot;
+        inner_w.version += 1;
+        if let Some(deal) = inner_w.deals.last_mut() {
+            deal.taken.push(new_word.to_string());
+        }
 
         inner_w.players_boards[player_index].add_word(new_word.to_string());
-
-        // Store last move for potential challenge
-        inner_w.last_move = Some(LastMove {
-            attacker_id: *player_id,
-            victim_id: None,
-            word_taken: new_word.to_string(),
-            word_stolen: None,
-            old_pot,
-        });
+        let scores = self.score_breakdown(&inner_w);
+        apply_scores(&mut self.game_state.players, &scores);
 
         let completed = AnagramCompletedData {
             game_state: self.game_state.clone(),
             pot: inner_w.pot.clone(),
             players_boards: inner_w.players_boards.clone(),
             chat: self.game_state.chat.clone(),
+            moves: inner_w.history.moves(),
+            description,
         };
+        drop(inner_w);
+        self.word_taken();
 
         let msg = Message {
             kind: "anagram_complete".into(),
@@ -469,13 +1158,14 @@ impl Anagrams {
         let _ = self.game_state.broadcast_tx.send(json);
     }
 
+    /// Apply a steal: each of `victims` (owner, word) loses that word and the attacker gains
+    /// `new_word`.
     fn sync_state(
         &mut self,
-        new_pot: Vec<char>,
+        new_pot: Vec<String>,
         new_word: &str,
-        victim_word_index: usize,
+        victims: &[(Uuid, String)],
         attacker_id: &Uuid,
-        victim_id: &Uuid,
     ) {
         // perform modifications under write lock
         let mut inner_w = self.inner.write().unwrap();
@@ -486,100 +1176,488 @@ impl Anagrams {
             .position(|x| x.player.id == *attacker_id)
             .unwrap();
 
-        let victim_index = inner_w
-            .players_boards
+        // Record this move for potential challenge before changing state
+        let pot_tiles = pot_tiles_used(&inner_w.pot, &new_pot);
+        let move_id = inner_w
+            .history
+            .record(*attacker_id, new_word, victims.to_vec(), pot_tiles);
+        let description = describe_move(
+            inner_w.history.get(move_id).unwrap(),
+            &inner_w.players_boards,
+        );
+
+        inner_w.pot = new_pot;
+        for (victim_id, word) in victims {
+            if let Some(victim_board) = inner_w
+                .players_boards
+                .iter_mut()
+                .find(|pb| pb.player.id == *victim_id)
+            {
+                victim_board.remove_word(word);
+            }
+            *inner_w.stolen_from.entry(*victim_id).or_insert(0) += 1;
+        }
+
+        inner_w.version += 1;
+        if let Some(deal) = inner_w.deals.last_mut() {
+            deal.taken.push(new_word.to_string());
+        }
+        inner_w.players_boards[attacker_index].add_word(new_word.to_string());
+        let scores = self.score_breakdown(&inner_w);
+        apply_scores(&mut self.game_state.players, &scores);
+
+        let completed = AnagramCompletedData {
+            game_state: self.game_state.clone(),
+            pot: inner_w.pot.clone(),
+            players_boards: inner_w.players_boards.clone(),
+            chat: self.game_state.chat.clone(),
+            moves: inner_w.history.moves(),
+            description,
+        };
+        drop(inner_w);
+        self.word_taken();
+
+        let msg = Message {
+            kind: "anagram_complete".into(),
+            data: serde_json::to_string(&completed).unwrap(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let _ = self.game_state.broadcast_tx.send(json);
+    }
+
+    fn broadcast_state(&self, kind: String) {
+        let inner_r = self.inner.read().unwrap();
+        let state_data = GameStateData {
+            game_state: self.game_state.clone(),
+            pot: inner_r.pot.clone(),
+            players_boards: inner_r.players_boards.clone(),
+            chat: self.game_state.chat.clone(),
+            moves: inner_r.history.moves(),
+            settings: self.settings.clone(),
+            flipper: self.flipper().map(|p| p.id),
+            scores: (self.game_state.current_state == "game_over")
+                .then(|| self.score_breakdown(&inner_r)),
+            missed: self.missed_report(),
+        };
+
+        let msg = Message {
+            kind,
+            data: serde_json::to_string(&state_data).unwrap(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let _ = self.game_state.broadcast_tx.send(json);
+    }
+
+    /// The `/define` reply for `word`.
+    fn define(&self, word: &str) -> Result<String, String> {
+        let word = self.language.normalize(word.trim());
+        if word.is_empty() {
+            return Err("Usage: /define <word>".into());
+        }
+        if !self.dictionary.contains(&word) {
+            return Err(format!(
+                "{} is not in the {} word list.",
+                word.to_uppercase(),
+                self.dictionary.name
+            ));
+        }
+        Ok(match self.dictionary.define(&word) {
+            Some(definition) => format!("{}: {}", word.to_uppercase(), definition),
+            None => format!(
+                "{} is a valid word, but no definition is available.",
+                word.to_uppercase()
+            ),
+        })
+    }
+
+    /// A word was taken: the final countdown starts over and any votes to end are void.
+    fn word_taken(&mut self) {
+        if let Some(countdown) = self.countdown.as_mut() {
+            countdown.reset();
+        }
+        self.end_votes.clear();
+    }
+
+    /// Finish the game: stop dealing, settle the scores and broadcast `game_over`.
+    fn end_game(&mut self, announcement: &str) {
+        self.game_state.current_state = "game_over".into();
+        self.countdown = None;
+        self.queue_missed(usize::MAX, true);
+        self.game_state.chat.push(super::ChatMessage {
+            sender: "System".to_string(),
+            text: announcement.to_string(),
+            cards: None,
+            message_type: Some("info".to_string()),
+        });
+        {
+            let mut inner_w = self.inner.write().unwrap();
+            inner_w.paused = true;
+            let scores = self.score_breakdown(&inner_w);
+            apply_scores(&mut self.game_state.players, &scores);
+        }
+        self.broadcast_state("game_over".into());
+    }
+
+    /// Send the deals before `end` that haven't been yet to the solver thread; `last` once
+    /// the game is over.
+    fn queue_missed(&mut self, end: usize, last: bool) {
+        let deals = {
+            let inner_r = self.inner.read().unwrap();
+            let end = end.min(inner_r.deals.len());
+            inner_r.deals[self.deals_queued.min(end)..end].to_vec()
+        };
+        self.deals_queued += deals.len();
+        let _ = self.solver_tx.send(SolveJob::Missed { deals, last });
+    }
+
+    /// The missed-plays report, once the game is over and every deal has been solved.
+    fn missed_report(&self) -> Option<Vec<MissedDeal>> {
+        let report = self.missed.lock().unwrap();
+        report.complete.then(|| report.deals.clone())
+    }
+
+    fn vote_to_end(&mut self, player_id: &Uuid) -> Result<(), &str> {
+        if !self.settings.endgame.vote_to_end {
+            return Err("Voting to end isn't enabled in this room.");
+        }
+        if !self.inner.read().unwrap().bag.letters.is_empty() {
+            return Err("You can vote to end once the bag is empty.");
+        }
+        self.end_votes.insert(*player_id);
+
+        let player_name = self
+            .game_state
+            .players
+            .iter()
+            .find(|p| p.id == *player_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let (votes, voters) = self.end_vote_tally();
+        let chat_msg = super::ChatMessage {
+            sender: "System".to_string(),
+            text: format!(
+                "{} voted to end the game ({}/{}).",
+                player_name, votes, voters
+            ),
+            cards: None,
+            message_type: Some("info".to_string()),
+        };
+        self.game_state.chat.push(chat_msg.clone());
+        let msg = Message {
+            kind: "chat".into(),
+            data: serde_json::to_string(&chat_msg).unwrap(),
+        };
+        let _ = self
+            .game_state
+            .broadcast_tx
+            .send(serde_json::to_string(&msg).unwrap());
+
+        self.check_end_vote();
+        Ok(())
+    }
+
+    /// Votes to end from connected players, and how many players are connected.
+    fn end_vote_tally(&self) -> (usize, usize) {
+        let connected: Vec<&Player> = self
+            .game_state
+            .players
             .iter()
-            .position(|x| x.player.id == *victim_id)
-            .unwrap();
+            .filter(|p| p.is_connected())
+            .collect();
+        let votes = connected
+            .iter()
+            .filter(|p| self.end_votes.contains(&p.id))
+            .count();
+        (votes, connected.len())
+    }
 
-        let attacker_name = inner_w.players_boards[attacker_index].player.name.clone();
-        let victim_name = inner_w.players_boards[victim_index].player.name.clone();
-        let victim_word = inner_w.players_boards[victim_index].words[victim_word_index].clone();
+    /// End the game once every connected player has voted to, which can also happen when the
+    /// last holdout leaves.
+    fn check_end_vote(&mut self) {
+        let (votes, voters) = self.end_vote_tally();
+        if votes > 0 && votes == voters {
+            self.end_game("Everyone voted to end the game. Final scores have been calculated.");
+        }
+    }
 
-        let _chat_message_text = format!(
-            "{} took {} from {}'s {}!",
-            attacker_name, new_word, victim_name, victim_word
-        );
+    /// Index of whoever's turn it is to flip, in turn-based dealing: the next connected
+    /// player from `flip_turn`, so a player who has left doesn't hold up the deal.
+    fn flipper_index(&self) -> Option<usize> {
+        if self.settings.deal != DealMode::Turns {
+            return None;
+        }
+        let players = &self.game_state.players;
+        (0..players.len())
+            .map(|i| (self.flip_turn + i) % players.len())
+            .find(|&i| players[i].is_connected())
+    }
 
-        // self.game_state.chat.push(super::ChatMessage {
-        //     sender: "System".to_string(),
-        //     text: chat_message_text,
-        //     cards: None,
-        //     message_type: Some("success".to_string()),
-        // });
+    /// Whose turn it is to flip, in turn-based dealing.
+    fn flipper(&self) -> Option<&Player> {
+        self.flipper_index().map(|i| &self.game_state.players[i])
+    }
 
-        // Record this move before changing state
-        let old_pot = inner_w.pot.clone();
+    /// Flip votes from connected players, and how many are needed: a majority of them.
+    fn flip_vote_tally(&self) -> (usize, usize) {
+        let connected: Vec<&Player> = self
+            .game_state
+            .players
+            .iter()
+            .filter(|p| p.is_connected())
+            .collect();
+        let votes = connected
+            .iter()
+            .filter(|p| self.flip_votes.contains(&p.id))
+            .count();
+        (votes, deal_mode::votes_needed(connected.len()))
+    }
 
-        inner_w.pot = new_pot;
-        inner_w.players_boards[victim_index]
-            .words
-            .remove(victim_word_index);
+    /// Tell everyone whose turn it is to flip.
+    fn announce_flipper(&self) {
+        let Some(flipper) = self.flipper() else {
+            return;
+        };
+        let turn = FlipTurnData {
+            player_id: flipper.id,
+            name: flipper.name.clone(),
+        };
+        let msg = Message {
+            kind: "flip_turn".into(),
+            data: serde_json::to_string(&turn).unwrap(),
+        };
+        let _ = self
+            .game_state
+            .broadcast_tx
+            .send(serde_json::to_string(&msg).unwrap());
+    }
 
-        inner_w.players_boards[attacker_index].add_word(new_word.to_string());
+    /// A player's `/flip`: deals a tile on their turn, or counts as their vote to flip.
+    fn flip(&mut self, player_id: &Uuid) -> Result<(), &str> {
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.");
+        }
+        {
+            let inner_r = self.inner.read().unwrap();
+            if inner_r.paused {
+                return Err("Game is paused.");
+            }
+            if inner_r.bag.letters.is_empty() {
+                return Err("No more tiles remaining.");
+            }
+        }
 
-        // Store last move for potential challenge
-        inner_w.last_move = Some(LastMove {
-            attacker_id: *attacker_id,
-            victim_id: Some(*victim_id),
-            word_taken: new_word.to_string(),
-            word_stolen: Some(victim_word.clone()),
-            old_pot,
-        });
+        match self.settings.deal {
+            DealMode::Timer { .. } => return Err("Tiles are flipped on a timer in this room."),
+            DealMode::Turns => {
+                let Some(turn) = self
+                    .flipper_index()
+                    .filter(|&i| self.game_state.players[i].id == *player_id)
+                else {
+                    return Err("It's not your turn to flip.");
+                };
+                self.flip_turn = (turn + 1) % self.game_state.players.len();
+            }
+            DealMode::Vote => {
+                self.flip_votes.insert(*player_id);
+                let (votes, needed) = self.flip_vote_tally();
+                if votes < needed {
+                    let player_name = self
+                        .game_state
+                        .players
+                        .iter()
+                        .find(|p| p.id == *player_id)
+                        .map(|p| p.name.clone())
+                        .unwrap_or_else(|| "Unknown".to_string());
+                    let chat_msg = super::ChatMessage {
+                        sender: "System".to_string(),
+                        text: format!("{} wants to flip ({}/{}).", player_name, votes, needed),
+                        cards: None,
+                        message_type: Some("info".to_string()),
+                    };
+                    self.game_state.chat.push(chat_msg.clone());
+                    let msg = Message {
+                        kind: "chat".into(),
+                        data: serde_json::to_string(&chat_msg).unwrap(),
+                    };
+                    let _ = self
+                        .game_state
+                        .broadcast_tx
+                        .send(serde_json::to_string(&msg).unwrap());
+                    return Ok(());
+                }
+                self.flip_votes.clear();
+            }
+        }
 
-        let completed = AnagramCompletedData {
-            game_state: self.game_state.clone(),
-            pot: inner_w.pot.clone(),
-            players_boards: inner_w.players_boards.clone(),
-            chat: self.game_state.chat.clone(),
-        };
+        deal_tile(
+            &mut self.inner.write().unwrap(),
+            &self.game_state.broadcast_tx,
+        );
+        self.announce_flipper();
+        Ok(())
+    }
 
-        let msg = Message {
-            kind: "anagram_complete".into(),
-            data: serde_json::to_string(&completed).unwrap(),
-        };
-        let json = serde_json::to_string(&msg).unwrap();
-        let _ = self.game_state.broadcast_tx.send(json);
+    /// Catch up with players who have left: pass the turn on from a flipper who has gone, and
+    /// deal if the flip vote now has a majority of those still here.
+    fn check_flip_turn(&mut self) {
+        let players = self.game_state.players.len();
+        match self.settings.deal {
+            DealMode::Turns => {
+                if let Some(turn) = self.flipper_index() {
+                    if turn != self.flip_turn % players {
+                        self.flip_turn = turn;
+                        self.announce_flipper();
+                    }
+                }
+            }
+            DealMode::Vote => {
+                let (votes, needed) = self.flip_vote_tally();
+                if votes == 0 || votes < needed {
+                    return;
+                }
+                {
+                    let inner_r = self.inner.read().unwrap();
+                    if inner_r.paused || inner_r.bag.letters.is_empty() {
+                        return;
+                    }
+                }
+                self.flip_votes.clear();
+                deal_tile(
+                    &mut self.inner.write().unwrap(),
+                    &self.game_state.broadcast_tx,
+                );
+            }
+            DealMode::Timer { .. } => {}
+        }
     }
 
-    fn broadcast_state(&self, kind: String) {
+    /// Every player's score under the room's scoring settings.
+    fn score_breakdown(&self, inner: &Inner) -> Vec<ScoreBreakdown> {
+        inner
+            .players_boards
+            .iter()
+            .map(|board| {
+                let stolen = inner
+                    .stolen_from
+                    .get(&board.player.id)
+                    .copied()
+                    .unwrap_or(0);
+                let mut score = self.settings.scoring.score(self.language, board, stolen);
+                let hints = inner.hints_used.get(&board.player.id).copied().unwrap_or(0);
+                score.hint_penalty = (hints * self.settings.hints.cost) as i32;
+                score.total -= score.hint_penalty;
+                score
+            })
+            .collect()
+    }
+
+    /// The best play on the table right now, for `/hint`. It's charged separately, once the
+    /// hint has been sent.
+    fn hint(&self) -> Result<String, String> {
+        if !self.settings.hints.enabled {
+            return Err("Hints aren't enabled in this room.".into());
+        }
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.".into());
+        }
         let inner_r = self.inner.read().unwrap();
-        let state_data = GameStateData {
-            game_state: self.game_state.clone(),
-            pot: inner_r.pot.clone(),
-            players_boards: inner_r.players_boards.clone(),
-            chat: self.game_state.chat.clone(),
+        let table: Vec<(Uuid, String)> = inner_r
+            .players_boards
+            .iter()
+            .flat_map(|board| {
+                board
+                    .words
+                    .iter()
+                    .map(|word| (board.player.id, word.clone()))
+            })
+            .collect();
+        let solver = anagrams_solver::solver_for(self.language, &self.dictionary);
+        let plays = solver.solve(
+            &inner_r.pot,
+            &table,
+            |old, new| steal_rules::check_all(&self.settings.steal_rules, old, new).is_ok(),
+            1,
+        );
+        let Some(best) = plays.first() else {
+            return Err("There's nothing to play right now.".into());
         };
-
-        let msg = Message {
-            kind,
-            data: serde_json::to_string(&state_data).unwrap(),
+        let text = match (&best.victim, &best.stolen_word) {
+            (Some(victim), Some(stolen)) => format!(
+                "Hint: {}, stealing {}'s {}.",
+                best.word.to_uppercase(),
+                player_name(&inner_r.players_boards, victim),
+                stolen
+            ),
+            _ => format!("Hint: {} from the pot.", best.word.to_uppercase()),
         };
-        let json = serde_json::to_string(&msg).unwrap();
-        let _ = self.game_state.broadcast_tx.send(json);
+        Ok(text)
     }
 
-    fn start_challenge(&mut self, challenger_id: &Uuid) -> Result<(), &str> {
+    /// Charge `player_id` the hint cost.
+    fn charge_hint(&mut self, player_id: &Uuid) {
         let mut inner_w = self.inner.write().unwrap();
+        *inner_w.hints_used.entry(*player_id).or_insert(0) += 1;
+        let scores = self.score_breakdown(&inner_w);
+        apply_scores(&mut self.game_state.players, &scores);
+    }
 
-        // Check if there's a move to challenge
-        if inner_w.last_move.is_none() {
-            return Err("No move to challenge.");
+    /// How many of each tile are still in the bag (not counting the pot).
+    fn bag_counts(&self) -> BagCountsData {
+        let inner_r = self.inner.read().unwrap();
+        BagCountsData {
+            remaining: inner_r.bag.letters.len(),
+            tiles: self
+                .distribution
+                .iter()
+                .map(|(tile, total)| TileCount {
+                    tile: tile.clone(),
+                    remaining: inner_r.bag.remaining(tile),
+                    total: *total,
+                })
+                .collect(),
+        }
+    }
+
+    /// Challenge move `move_id`, or the latest move if None.
+    fn start_challenge(&mut self, challenger_id: &Uuid, move_id: Option<u64>) -> Result<(), &str> {
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.");
         }
+        let mut inner_w = self.inner.write().unwrap();
 
         // Check if already in a challenge
         if inner_w.active_challenge {
             return Err("A challenge is already in progress.");
         }
 
+        // Check if there's a move to challenge
+        let challenged = match move_id {
+            Some(id) => inner_w
+                .history
+                .get(id)
+                .ok_or("That move can no longer be challenged.")?,
+            None => inner_w.history.latest().ok_or("No move to challenge.")?,
+        };
+        if challenged.upheld {
+            return Err("That move has already survived a challenge.");
+        }
+        if challenged.attacker_id == *challenger_id {
+            return Err("You can't challenge your own move.");
+        }
+        let challenged = challenged.clone();
+
         // Start the challenge
+        inner_w.challenged_move = Some(challenged.id);
         inner_w.active_challenge = true;
         inner_w.paused = true;
         inner_w.challenge_votes.clear();
         inner_w.challenge_votes.insert(*challenger_id, true);
 
         // Update game state
-        self.game_state.current_state = "challenge".into();
+        self.interrupted_state = Some(std::mem::replace(
+            &mut self.game_state.current_state,
+            "challenge".into(),
+        ));
 
         // Get challenger name
         let challenger_name = inner_w
@@ -589,12 +1667,46 @@ impl Anagrams {
             .map(|pb| pb.player.name.clone())
             .unwrap_or_else(|| "Unknown".to_string());
 
+        let attacker_name = inner_w
+            .players_boards
+            .iter()
+            .find(|pb| pb.player.id == challenged.attacker_id)
+            .map(|pb| pb.player.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+
         drop(inner_w);
 
+        // the referee list, if any, rules straight away
+        let verdict = self
+            .referee
+            .as_ref()
+            .map(|referee| referee_verdict(referee, self.language, &challenged));
+
+        if let Some(verdict) = verdict {
+            let ruling = match &verdict {
+                Ok(ruling) | Err(ruling) => ruling.clone(),
+            };
+            self.game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!(
+                    "{} has challenged {}'s {}. {}",
+                    challenger_name, attacker_name, challenged.word_taken, ruling
+                ),
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+            self.resolve_challenge(verdict.is_err());
+            return Ok(());
+        }
+
+        let timeout_secs = self.settings.challenge.timeout_secs;
+        self.challenge_deadline =
+            Some(Instant::now() + std::time::Duration::from_secs(timeout_secs));
+
         // Broadcast challenge started
         let chat_msg = super::ChatMessage {
             sender: "System".to_string(),
-            text: format!("{} has challenged the last move! Type /challenge to agree or /maintain to disagree. Game is paused.", challenger_name),
+            text: format!("{} has challenged {}'s {}! Type /challenge to agree or /maintain to disagree. Voting closes in {} seconds. Game is paused.", challenger_name, attacker_name, challenged.word_taken, timeout_secs),
             cards: None,
             message_type: Some("info".to_string()),
         };
@@ -615,6 +1727,14 @@ impl Anagrams {
             return Err("No active challenge.");
         }
 
+        if inner_w
+            .challenged_move
+            .and_then(|id| inner_w.history.get(id))
+            .is_some_and(|m| m.attacker_id == *player_id)
+        {
+            return Err("You can't vote on a challenge to your own move.");
+        }
+
         // Record the vote
         inner_w.challenge_votes.insert(*player_id, vote);
 
@@ -659,26 +1779,34 @@ impl Anagrams {
             return;
         }
 
-        let total_players = inner_r.players_boards.len();
-        let votes_count = inner_r.challenge_votes.len();
-
-        let challenge_votes = inner_r.challenge_votes.values().filter(|&&v| v).count();
-        let maintain_votes = votes_count - challenge_votes;
-
-        // Calculate thresholds
-        let challenge_threshold = (total_players + 1) / 2; // half rounded up
-        let maintain_threshold = total_players / 2 + 1; // more than half
+        let verdict = challenge::tally(&inner_r.challenge_votes, &challenge_voters(&inner_r));
 
         drop(inner_r);
 
         // Resolve if threshold met
-        if challenge_votes >= challenge_threshold {
-            self.resolve_challenge(true);
-        } else if maintain_votes >= maintain_threshold {
-            self.resolve_challenge(false);
+        if let Some(challenge_succeeds) = verdict {
+            self.resolve_challenge(challenge_succeeds);
         }
     }
 
+    /// Settle a challenge whose voting time has run out by the votes cast so far.
+    fn expire_challenge(&mut self) {
+        let challenge_succeeds = {
+            let inner_r = self.inner.read().unwrap();
+            if !inner_r.active_challenge {
+                return;
+            }
+            challenge::timeout_verdict(&inner_r.challenge_votes, &challenge_voters(&inner_r))
+        };
+        self.game_state.chat.push(super::ChatMessage {
+            sender: "System".to_string(),
+            text: "Voting on the challenge has closed.".to_string(),
+            cards: None,
+            message_type: Some("info".to_string()),
+        });
+        self.resolve_challenge(challenge_succeeds);
+    }
+
     fn resolve_challenge(&mut self, challenge_succeeds: bool) {
         let mut inner_w = self.inner.write().unwrap();
 
@@ -687,44 +1815,54 @@ impl Anagrams {
         }
 
         inner_w.active_challenge = false;
-        inner_w.paused = false;
-        self.game_state.current_state = "in_progress".into();
-
-        if challenge_succeeds {
-            // Revert the last move
-            if let Some(last_move) = inner_w.last_move.take() {
-                // Find attacker board and remove the word they took
-                if let Some(attacker_board) = inner_w
-                    .players_boards
-                    .iter_mut()
-                    .find(|pb| pb.player.id == last_move.attacker_id)
-                {
-                    attacker_board.remove_word(&last_move.word_taken);
-                }
+        self.challenge_deadline = None;
+        // pick up where the challenge interrupted, unless the game ended meanwhile
+        let interrupted = self
+            .interrupted_state
+            .take()
+            .unwrap_or_else(|| "in_progress".into());
+        if self.game_state.current_state == "challenge" {
+            inner_w.paused = interrupted == "paused";
+            self.game_state.current_state = interrupted;
+        }
+        let resumed = match &self.game_state.current_state[..] {
+            "in_progress" => " Game resumed.",
+            "paused" => " The game is still paused.",
+            _ => "",
+        };
 
-                // If there was a victim, restore their word
-                if let (Some(victim_id), Some(stolen_word)) =
-                    (last_move.victim_id, last_move.word_stolen)
-                {
-                    if let Some(victim_board) = inner_w
-                        .players_boards
-                        .iter_mut()
-                        .find(|pb| pb.player.id == victim_id)
-                    {
-                        victim_board.add_word(stolen_word);
-                    }
-                }
+        let challenged_move = inner_w.challenged_move.take();
 
-                // Restore the pot
-                inner_w.pot = last_move.old_pot;
+        if challenge_succeeds {
+            // Undo the move, and first every later move built on the word it made
+            let plan = challenged_move
+                .and_then(|id| inner_w.history.reversal_plan(id))
+                .unwrap_or_default();
+            for undone in &plan {
+                revert_move(&mut inner_w, undone);
             }
+            inner_w.version += 1;
+            let undone_ids: Vec<u64> = plan.iter().map(|m| m.id).collect();
+            inner_w.history.remove(&undone_ids);
+            let scores = self.score_breakdown(&inner_w);
+            apply_scores(&mut self.game_state.players, &scores);
 
             drop(inner_w);
 
+            let text = match plan.len() {
+                0 | 1 => format!(
+                    "Challenge succeeded! The move has been reverted.{}",
+                    resumed
+                ),
+                n => format!(
+                    "Challenge succeeded! The move and {} later moves built on it have been reverted.{}",
+                    n - 1,
+                    resumed
+                ),
+            };
             let chat_msg = super::ChatMessage {
                 sender: "System".to_string(),
-                text: "Challenge succeeded! The last move has been reverted. Game resumed."
-                    .to_string(),
+                text,
                 cards: None,
                 message_type: Some("success".to_string()),
             };
@@ -732,13 +1870,15 @@ impl Anagrams {
 
             self.broadcast_state("challenge_resolved".into());
         } else {
-            // Challenge failed, clear last_move
-            inner_w.last_move = None;
+            // Challenge failed; the move can't be challenged again
+            if let Some(upheld) = challenged_move.and_then(|id| inner_w.history.get_mut(id)) {
+                upheld.upheld = true;
+            }
             drop(inner_w);
 
             let chat_msg = super::ChatMessage {
                 sender: "System".to_string(),
-                text: "Challenge failed! The move stands. Game resumed.".to_string(),
+                text: format!("Challenge failed! The move stands.{}", resumed),
                 cards: None,
                 message_type: Some("info".to_string()),
             };
@@ -756,6 +1896,12 @@ impl super::Game for Anagrams {
             pot: inner_r.pot.clone(),
             players_boards: inner_r.players_boards.clone(),
             chat: self.game_state.chat.clone(),
+            moves: inner_r.history.moves(),
+            settings: self.settings.clone(),
+            flipper: self.flipper().map(|p| p.id),
+            scores: (self.game_state.current_state == "game_over")
+                .then(|| self.score_breakdown(&inner_r)),
+            missed: self.missed_report(),
         };
 
         let msg = Message {
@@ -774,6 +1920,78 @@ impl super::Game for Anagrams {
         &self.game_state
     }
 
+    fn tick(&mut self) {
+        if self.game_state.current_state == "game_over" {
+            return;
+        }
+        if let Some(deadline) = self.challenge_deadline {
+            // players may have left since the last vote, changing the quorum
+            self.check_challenge_resolution();
+            if self.challenge_deadline.is_some() && Instant::now() >= deadline {
+                self.expire_challenge();
+            }
+        }
+        self.tick_bots();
+        self.check_flip_turn();
+        self.check_end_vote();
+        if self.game_state.current_state == "game_over" {
+            return;
+        }
+        // the newest deal can still be played on; the ones it replaced can be solved
+        let replaced = self.inner.read().unwrap().deals.len().saturating_sub(1);
+        if replaced > self.deals_queued {
+            self.queue_missed(replaced, false);
+        }
+        let Some(secs) = self.settings.endgame.countdown_secs else {
+            return;
+        };
+        let (bag_empty, paused) = {
+            let inner_r = self.inner.read().unwrap();
+            (inner_r.bag.letters.is_empty(), inner_r.paused)
+        };
+        if !bag_empty {
+            return;
+        }
+
+        let now = Instant::now();
+        if self.countdown.is_none() {
+            self.countdown = Some(FinalCountdown::new(secs, now));
+            self.game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!(
+                    "The bag is empty! The game ends after {} seconds without a word taken.",
+                    secs
+                ),
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+            let btx = self.game_state.broadcast_tx.clone();
+            self.send_state_to_client(&btx, "endgame_started".into());
+        }
+        let Some(countdown) = self.countdown.as_mut() else {
+            return;
+        };
+        countdown.tick(now, paused);
+        if countdown.next_announcement().is_some() {
+            let clock = EndgameClockData {
+                remaining_ms: countdown.remaining_ms(),
+            };
+            let msg = Message {
+                kind: "endgame_clock".into(),
+                data: serde_json::to_string(&clock).unwrap(),
+            };
+            let _ = self
+                .game_state
+                .broadcast_tx
+                .send(serde_json::to_string(&msg).unwrap());
+        }
+        if countdown.is_expired() {
+            self.end_game(
+                "Time's up! No more words were taken. Final scores have been calculated.",
+            );
+        }
+    }
+
     fn handle_game_socket_message(&mut self, txt: String) {
         let json_in = serde_json::from_str::<serde_json::Value>(&txt);
         if let Ok(parsed) = json_in {
@@ -803,36 +2021,181 @@ impl super::Game for Anagrams {
 
                         // Check if the message is the /gameover command
                         if message.trim() == "/gameover" {
-                            // Set game state to game_over
-                            self.game_state.current_state = "game_over".into();
+                            if self.game_state.current_state == "game_over" {
+                                let chat_msg = super::ChatMessage {
+                                    sender: "System".to_string(),
+                                    text: "The game is already over.".to_string(),
+                                    cards: None,
+                                    message_type: Some("error".to_string()),
+                                };
+                                self.game_state.chat.push(chat_msg.clone());
+                                let chat = Message {
+                                    kind: "chat".into(),
+                                    data: serde_json::to_string(&chat_msg).unwrap(),
+                                };
+                                let _ = self
+                                    .game_state
+                                    .broadcast_tx
+                                    .send(serde_json::to_string(&chat).unwrap());
+                            } else {
+                                self.end_game("Game Over! Final scores have been calculated.");
+                            }
+                        } else if message.trim() == "/flip" {
+                            if let Some(player) =
+                                self.game_state.players.iter().find(|p| p.name == sender)
+                            {
+                                let player_id = player.id;
 
-                            // Add system message to chat
-                            self.game_state.chat.push(super::ChatMessage {
+                                if let Err(e) = self.flip(&player_id) {
+                                    let chat_msg = super::ChatMessage {
+                                        sender: "System".to_string(),
+                                        text: e.to_string(),
+                                        cards: None,
+                                        message_type: Some("error".to_string()),
+                                    };
+                                    self.game_state.chat.push(chat_msg.clone());
+                                    let chat = Message {
+                                        kind: "chat".into(),
+                                        data: serde_json::to_string(&chat_msg).unwrap(),
+                                    };
+                                    let _ = self
+                                        .game_state
+                                        .broadcast_tx
+                                        .send(serde_json::to_string(&chat).unwrap());
+                                }
+                            }
+                        } else if message.trim() == "/define"
+                            || message.trim().starts_with("/define ")
+                        {
+                            let (text, message_type) =
+                                match self.define(&message.trim()["/define".len()..]) {
+                                    Ok(text) => (text, "info"),
+                                    Err(e) => (e, "error"),
+                                };
+                            let chat_msg = super::ChatMessage {
                                 sender: "System".to_string(),
-                                text: "Game Over! Final scores have been calculated.".to_string(),
+                                text,
                                 cards: None,
-                                message_type: Some("info".to_string()),
-                            });
-
-                            // Calculate final scores based on number of words
+                                message_type: Some(message_type.to_string()),
+                            };
+                            self.game_state.chat.push(chat_msg.clone());
+                            let chat = Message {
+                                kind: "chat".into(),
+                                data: serde_json::to_string(&chat_msg).unwrap(),
+                            };
+                            let _ = self
+                                .game_state
+                                .broadcast_tx
+                                .send(serde_json::to_string(&chat).unwrap());
+                        } else if message.trim() == "/hint" {
+                            if let Some(player) =
+                                self.game_state.players.iter().find(|p| p.name == sender)
                             {
-                                let inner_r = self.inner.read().unwrap();
-                                for player_board in &inner_r.players_boards {
-                                    if let Some(player) = self
-                                        .game_state
-                                        .players
-                                        .iter_mut()
-                                        .find(|p| p.id == player_board.player.id)
-                                    {
-                                        player.score = player_board.words.len() as u32;
+                                let player_id = player.id;
+                                let connected = player.is_connected();
+
+                                // only the asker sees the hint, so it's only charged once it has
+                                // gone to them; everyone sees it was taken
+                                let sent = self.hint().and_then(|text| {
+                                    if !connected {
+                                        return Err("Hints are sent to you alone; rejoin the game to take one.".to_string());
+                                    }
+                                    let hint = super::ChatMessage {
+                                        sender: "System".to_string(),
+                                        text,
+                                        cards: None,
+                                        message_type: Some("info".to_string()),
+                                    };
+                                    let msg = DirectMessage {
+                                        kind: "chat".into(),
+                                        data: serde_json::to_string(&hint).unwrap(),
+                                        recipient: player_id,
+                                    };
+                                    self.game_state
+                                        .broadcast_tx
+                                        .send(serde_json::to_string(&msg).unwrap())
+                                        .map(|_| ())
+                                        .map_err(|_| "The hint couldn't be sent.".to_string())
+                                });
+                                match sent {
+                                    Ok(()) => {
+                                        self.charge_hint(&player_id);
+                                        self.game_state.chat.push(super::ChatMessage {
+                                            sender: "System".to_string(),
+                                            text: format!("{} took a hint.", sender),
+                                            cards: None,
+                                            message_type: Some("info".to_string()),
+                                        });
+                                        self.broadcast_state("hint".into());
+                                    }
+                                    Err(e) => {
+                                        let chat_msg = super::ChatMessage {
+                                            sender: "System".to_string(),
+                                            text: e,
+                                            cards: None,
+                                            message_type: Some("error".to_string()),
+                                        };
+                                        self.game_state.chat.push(chat_msg.clone());
+                                        let chat = Message {
+                                            kind: "chat".into(),
+                                            data: serde_json::to_string(&chat_msg).unwrap(),
+                                        };
+                                        let _ = self
+                                            .game_state
+                                            .broadcast_tx
+                                            .send(serde_json::to_string(&chat).unwrap());
                                     }
                                 }
                             }
+                        } else if message.trim() == "/end" {
+                            if let Some(player) =
+                                self.game_state.players.iter().find(|p| p.name == sender)
+                            {
+                                let player_id = player.id;
 
-                            // Broadcast the updated game state with game_over status
-                            let btx = self.game_state.broadcast_tx.clone();
-                            self.send_state_to_client(&btx, "game_over".into());
+                                if let Err(e) = self.vote_to_end(&player_id) {
+                                    let chat_msg = super::ChatMessage {
+                                        sender: "System".to_string(),
+                                        text: e.to_string(),
+                                        cards: None,
+                                        message_type: Some("error".to_string()),
+                                    };
+                                    self.game_state.chat.push(chat_msg.clone());
+                                    let chat = Message {
+                                        kind: "chat".into(),
+                                        data: serde_json::to_string(&chat_msg).unwrap(),
+                                    };
+                                    let _ = self
+                                        .game_state
+                                        .broadcast_tx
+                                        .send(serde_json::to_string(&chat).unwrap());
+                                }
+                            }
                         } else if message.trim() == "/pause" {
+                            // a challenge pauses and resumes play itself, and a finished game stays finished
+                            let refusal = match &self.game_state.current_state[..] {
+                                "game_over" => Some("The game is over."),
+                                "challenge" => Some("Wait for the challenge to be settled."),
+                                _ => None,
+                            };
+                            if let Some(refusal) = refusal {
+                                let chat_msg = super::ChatMessage {
+                                    sender: "System".to_string(),
+                                    text: refusal.to_string(),
+                                    cards: None,
+                                    message_type: Some("error".to_string()),
+                                };
+                                self.game_state.chat.push(chat_msg.clone());
+                                let chat = Message {
+                                    kind: "chat".into(),
+                                    data: serde_json::to_string(&chat_msg).unwrap(),
+                                };
+                                let _ = self
+                                    .game_state
+                                    .broadcast_tx
+                                    .send(serde_json::to_string(&chat).unwrap());
+                                return;
+                            }
                             // Toggle paused state
                             let mut inner_w = self.inner.write().unwrap();
                             inner_w.paused = !inner_w.paused;
@@ -866,7 +2229,17 @@ impl super::Game for Anagrams {
                             let btx = self.game_state.broadcast_tx.clone();
                             let kind = if paused_now { "paused" } else { "resumed" };
                             self.send_state_to_client(&btx, kind.into());
-                        } else if message.trim() == "/challenge" {
+                        } else if message.trim() == "/challenge"
+                            || message.trim().starts_with("/challenge ")
+                        {
+                            // "/challenge 12" challenges move 12 rather than the latest
+                            let move_id = match message.trim()["/challenge".len()..].trim() {
+                                "" => Ok(None),
+                                arg => arg
+                                    .parse::<u64>()
+                                    .map(Some)
+                                    .map_err(|_| "Usage: /challenge [move number]"),
+                            };
                             // Find the player ID from sender name
                             if let Some(player) =
                                 self.game_state.players.iter().find(|p| p.name == sender)
@@ -900,7 +2273,9 @@ impl super::Game for Anagrams {
                                     }
                                 } else {
                                     // Start a new challenge
-                                    if let Err(e) = self.start_challenge(&player_id) {
+                                    if let Err(e) =
+                                        move_id.and_then(|id| self.start_challenge(&player_id, id))
+                                    {
                                         let chat_msg = super::ChatMessage {
                                             sender: "System".to_string(),
                                             text: e.to_string(),
@@ -974,6 +2349,16 @@ impl super::Game for Anagrams {
                         .get("player_name")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
+                    // optional word(s) being stolen: "target" for one, "targets" to combine
+                    let targets: Result<Vec<StealTarget>, String> =
+                        match (parsed.get("target"), parsed.get("targets")) {
+                            (Some(target), _) => serde_json::from_value(target.clone())
+                                .map(|t| vec![t])
+                                .map_err(|_| "Invalid steal target.".to_string()),
+                            (None, Some(targets)) => serde_json::from_value(targets.clone())
+                                .map_err(|_| "Invalid steal targets.".to_string()),
+                            (None, None) => Ok(Vec::new()),
+                        };
 
                     if let Some(data) = data {
                         if let Some(word_attempt) = data.as_str() {
@@ -1065,23 +2450,19 @@ impl super::Game for Anagrams {
                             // append to game chat history
                             self.game_state.chat.push(player_chat.clone());
                             // broadcast the chat message so all clients see the attempted word
-                            // Send the ChatMessage fields directly, not double-serialized
                             let chat_msg = Message {
                                 kind: "chat".into(),
-                                data: format!(
-                                    r#"{{"sender":"{}","message":"{}"}}"#,
-                                    sender_name, word_attempt
-                                ),
+                                data: serde_json::to_string(&player_chat).unwrap(),
                             };
                             let _ = btx.send(serde_json::to_string(&chat_msg).unwrap());
 
-                            if let Err(e) = self.anagram_attempt(word_attempt.clone(), player_id) {
-                                // Convert error to string first to avoid borrow issues
-                                let error_text = e.to_string();
+                            if let Err(error_text) = targets.and_then(|targets| {
+                                self.anagram_attempt(word_attempt.clone(), player_id, &targets)
+                            }) {
                                 // Broadcast system chat with the error message
                                 let chat_msg = super::ChatMessage {
                                     sender: "System".to_string(),
-                                    text: error_text.clone(),
+                                    text: error_text,
                                     cards: None,
                                     message_type: Some("error".to_string()),
                                 };
@@ -1089,16 +2470,40 @@ impl super::Game for Anagrams {
                                 self.game_state.chat.push(chat_msg.clone());
                                 let chat = Message {
                                     kind: "chat".into(),
-                                    data: format!(
-                                        r#"{{"sender":"System","message":"{}","message_type":"error"}}"#,
-                                        error_text
-                                    ),
+                                    data: serde_json::to_string(&chat_msg).unwrap(),
                                 };
                                 let _ = btx.send(serde_json::to_string(&chat).unwrap());
                             }
                         }
                     }
                 }
+                "bag_counts" => {
+                    let player_id = parsed
+                        .get("player_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| Uuid::parse_str(s).ok());
+                    if let Some(player_id) = player_id {
+                        let msg = DirectMessage {
+                            kind: "bag_counts".into(),
+                            data: serde_json::to_string(&self.bag_counts()).unwrap(),
+                            recipient: player_id,
+                        };
+                        let json = serde_json::to_string(&msg).unwrap();
+                        let _ = self.game_state.broadcast_tx.send(json);
+                    }
+                }
+                "add_bot" => {
+                    let difficulty = parsed
+                        .get("data")
+                        .and_then(|v| serde_json::from_value::<BotDifficulty>(v.clone()).ok())
+                        .unwrap_or_default();
+                    self.add_bot(difficulty);
+                    let btx = self.game_state.broadcast_tx.clone();
+                    self.send_state_to_client(&btx, "player_joined".into());
+                    if self.game_state.players.len() == 1 {
+                        self.announce_flipper();
+                    }
+                }
                 "join_player" => {
                     let data = parsed
                         .get("data")
@@ -1141,7 +2546,10 @@ impl super::Game for Anagrams {
 
                             // Broadcast updated game state to all clients
                             let btx = self.game_state.broadcast_tx.clone();
-                            let _ = self.send_state_to_client(&btx, "player_joined".into());
+                            self.send_state_to_client(&btx, "player_joined".into());
+                            if self.game_state.players.len() == 1 {
+                                self.announce_flipper();
+                            }
                         }
                     }
                 }
@@ -1164,17 +2572,23 @@ impl super::Game for Anagrams {
 mod tests {
     use super::*;
     use crate::game::player::Player;
+    use crate::game::word_scoring::ScoringScheme;
+    use crate::game::Game;
     use crate::user::User;
     use uuid::Uuid;
 
+    fn tiles(word: &str) -> Vec<String> {
+        word.chars().map(|c| c.to_string()).collect()
+    }
+
     #[test]
     fn test_some_anagram_success() {
         // existing word uses subset of letters in word_to_check
-        let existing = "eat";
-        let word_to_check = "treat";
-        let pot = vec!['t', 'r'];
+        let existing = tiles("eat");
+        let word_to_check = tiles("treat");
+        let pot = tiles("tr");
 
-        let result = some_anagram(existing, word_to_check, pot.clone());
+        let result = some_anagram(&existing, &word_to_check, pot.clone());
         // 't' and 'r' should be consumed leaving nothing
         assert!(result.is_some());
         let remaining = result.unwrap();
@@ -1183,14 +2597,82 @@ mod tests {
 
     #[test]
     fn test_some_anagram_failure_not_enough_letters() {
-        let existing = "apple";
-        let word_to_check = "ape"; // missing one 'p' for existing
-        let pot = vec!['x'];
+        let existing = tiles("apple");
+        let word_to_check = tiles("ape"); // missing one 'p' for existing
+        let pot = tiles("x");
 
-        let result = some_anagram(existing, word_to_check, pot);
+        let result = some_anagram(&existing, &word_to_check, pot);
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_digraph_tiles_count_as_one_letter() {
+        // Spanish "ll" is a single tile: "calle" is c, a, ll, e
+        let spanish = language::get("es").unwrap();
+        let calle = spanish.tiles("calle").unwrap();
+        assert!(some_anagram(&spanish.tiles("cal").unwrap(), &calle, tiles("le")).is_none());
+        let pot = vec!["ll".to_string(), "e".to_string()];
+        assert_eq!(pot_anagram(&calle, pot.clone()), None);
+        let pot = [pot, tiles("ca")].concat();
+        assert_eq!(pot_anagram(&calle, pot), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_languages_without_a_word_list_are_refused() {
+        // only the English list is bundled
+        let codes: Vec<&str> = language::available().iter().map(|p| p.code).collect();
+        assert_eq!(codes, vec!["en"]);
+        assert!(AnagramsSettings::default().check().is_ok());
+        let spanish = AnagramsSettings {
+            language: "es".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            spanish.check().unwrap_err(),
+            "Word list \"es\" for Español isn't installed."
+        );
+
+        // the lemmatizer is English-only, so shared roots aren't checked in other languages
+        let game = Anagrams::new(
+            "testgame".to_string(),
+            AnagramsSettings {
+                dictionary: Some(dictionary::DEFAULT_DICTIONARY.to_string()),
+                ..spanish
+            },
+        );
+        assert!(game.settings.steal_rules.is_empty());
+    }
+
+    #[test]
+    fn test_bag_counts_follow_preset() {
+        let settings = AnagramsSettings {
+            bag: BagPreset::Custom {
+                counts: [("a", 10), ("b", 15)]
+                    .iter()
+                    .map(|(t, n)| (t.to_string(), *n))
+                    .collect(),
+            },
+            ..Default::default()
+        };
+        let game = Anagrams::new("testgame".to_string(), settings);
+        let counts = game.bag_counts();
+        // one tile starts in the pot
+        assert_eq!(counts.remaining, 24);
+        assert_eq!(counts.tiles.len(), 2);
+        assert_eq!(counts.tiles.iter().map(|t| t.remaining).sum::<u32>(), 24);
+        assert_eq!(counts.tiles[1].total, 15);
+
+        let settings = AnagramsSettings {
+            bag: BagPreset::Custom {
+                counts: [("a".to_string(), 5)].into_iter().collect(),
+            },
+            ..Default::default()
+        };
+        let game = Anagrams::new("testgame".to_string(), settings);
+        assert_eq!(game.settings.bag, BagPreset::Standard);
+        assert_eq!(game.bag_counts().remaining, 142);
+    }
+
     #[test]
     fn test_playerboard_add_remove() {
         let user = User::new("Alice".to_string());
@@ -1205,11 +2687,578 @@ mod tests {
         assert!(!board.remove_word(&"nope".to_string()));
     }
 
+    #[test]
+    fn test_steal_rules_explain_rejection() {
+        let settings = AnagramsSettings {
+            steal_rules: vec![StealRule::MustRearrange],
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let p1 = Player::new("Alice".to_string(), Uuid::new_v4());
+        let p2 = Player::new("Bob".to_string(), Uuid::new_v4());
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            inner_w.players_boards.push(PlayerBoard::from_player(&p1));
+            let mut bob_board = PlayerBoard::from_player(&p2);
+            bob_board.add_word("eat".to_string());
+            inner_w.players_boards.push(bob_board);
+            inner_w.pot = tiles("tr");
+        }
+
+        assert_eq!(
+            game.anagram_attempt("treat".to_string(), p1.id, &[]),
+            Err("eat has to be rearranged, not just extended.".to_string())
+        );
+        assert!(game.anagram_attempt("rate".to_string(), p1.id, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_scores_follow_steals_and_reverts() {
+        let settings = AnagramsSettings {
+            steal_rules: vec![],
+            scoring: ScoringSettings {
+                scheme: ScoringScheme::LettersMinusTwo,
+                length_bonus: false,
+                steal_penalty: 2,
+            },
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let p1 = Player::new("Alice".to_string(), Uuid::new_v4());
+        let p2 = Player::new("Bob".to_string(), Uuid::new_v4());
+        game.game_state.players = vec![p1.clone(), p2.clone()];
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            inner_w.players_boards.push(PlayerBoard::from_player(&p1));
+            let mut bob_board = PlayerBoard::from_player(&p2);
+            bob_board.add_word("dog".to_string());
+            bob_board.add_word("eat".to_string());
+            inner_w.players_boards.push(bob_board);
+            inner_w.pot = tiles("tr");
+        }
+        let score = |game: &Anagrams, id: Uuid| {
+            game.game_state
+                .players
+                .iter()
+                .find(|p| p.id == id)
+                .unwrap()
+                .score
+        };
+
+        assert!(game
+            .anagram_attempt("treat".to_string(), p1.id, &[])
+            .is_ok());
+        assert_eq!(score(&game, p1.id), 3);
+        // dog is worth 1, less 2 for the stolen eat
+        assert_eq!(score(&game, p2.id), 0);
+
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            inner_w.active_challenge = true;
+            inner_w.challenged_move = inner_w.history.latest().map(|m| m.id);
+        }
+        game.resolve_challenge(true);
+        assert_eq!(score(&game, p1.id), 0);
+        assert_eq!(score(&game, p2.id), 2);
+
+        game.game_state.current_state = "game_over".into();
+        let (tx, mut rx) = broadcast::channel(4);
+        game.send_state_to_client(&tx, "game_over".into());
+        let msg: Message = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        let data: serde_json::Value = serde_json::from_str(&msg.data).unwrap();
+        assert_eq!(data["scores"].as_array().unwrap().len(), 2);
+        assert_eq!(data["scores"][1]["words"][1]["points"], 1);
+    }
+
+    #[test]
+    fn test_game_ends_after_bag_empties() {
+        let settings = AnagramsSettings {
+            endgame: EndgameSettings {
+                countdown_secs: Some(0),
+                vote_to_end: false,
+            },
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        game.tick();
+        assert!(game.countdown.is_none());
+
+        game.inner.write().unwrap().bag.letters.clear();
+        game.tick();
+        assert_eq!(game.game_state.current_state, "game_over");
+        assert!(game
+            .anagram_attempt("eat".to_string(), Uuid::new_v4(), &[])
+            .is_err());
+    }
+
+    #[test]
+    fn test_everyone_votes_to_end() {
+        let settings = AnagramsSettings {
+            endgame: EndgameSettings {
+                countdown_secs: None,
+                vote_to_end: true,
+            },
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let p1 = Player::new("Alice".to_string(), Uuid::new_v4());
+        let p2 = Player::new("Bob".to_string(), Uuid::new_v4());
+        game.game_state.players = vec![p1.clone(), p2.clone()];
+
+        assert!(game.vote_to_end(&p1.id).is_err());
+        game.inner.write().unwrap().bag.letters.clear();
+        game.tick();
+        assert!(game.countdown.is_none());
+
+        assert!(game.vote_to_end(&p1.id).is_ok());
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert!(game.vote_to_end(&p2.id).is_ok());
+        assert_eq!(game.game_state.current_state, "game_over");
+    }
+
+    #[test]
+    fn test_vote_to_end_counts_connected_players_only() {
+        let settings = AnagramsSettings {
+            endgame: EndgameSettings {
+                countdown_secs: None,
+                vote_to_end: true,
+            },
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let players: Vec<Player> = ["Alice", "Bob", "Cat"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        game.game_state.players = players.clone();
+        game.inner.write().unwrap().bag.letters.clear();
+
+        players[1].set_connected(false);
+        assert!(game.vote_to_end(&players[0].id).is_ok());
+        let tally = &game.game_state.chat.last().unwrap().text;
+        assert!(tally.ends_with("(1/2)."), "{}", tally);
+        assert_eq!(game.game_state.current_state, "in_progress");
+
+        // the last holdout leaving completes the vote
+        players[2].set_connected(false);
+        game.tick();
+        assert_eq!(game.game_state.current_state, "game_over");
+    }
+
+    #[test]
+    fn test_flipping_by_turns_and_by_vote() {
+        let players: Vec<Player> = ["Alice", "Bob", "Cat"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        let pot_len = |game: &Anagrams| game.inner.read().unwrap().pot.len();
+
+        let mut timer = Anagrams::new("testgame".to_string(), AnagramsSettings::default());
+        timer.game_state.players = players.clone();
+        assert!(timer.flip(&players[0].id).is_err());
+
+        let settings = AnagramsSettings {
+            deal: DealMode::Turns,
+            ..Default::default()
+        };
+        let mut turns = Anagrams::new("testgame".to_string(), settings);
+        turns.game_state.players = players.clone();
+        assert_eq!(turns.flipper().unwrap().id, players[0].id);
+        assert!(turns.flip(&players[1].id).is_err());
+        assert!(turns.flip(&players[0].id).is_ok());
+        assert_eq!(pot_len(&turns), 2);
+        assert_eq!(turns.flipper().unwrap().id, players[1].id);
+
+        let settings = AnagramsSettings {
+            deal: DealMode::Vote,
+            ..Default::default()
+        };
+        let mut vote = Anagrams::new("testgame".to_string(), settings);
+        vote.game_state.players = players.clone();
+        assert!(vote.flipper().is_none());
+        assert!(vote.flip(&players[2].id).is_ok());
+        assert!(vote.flip(&players[2].id).is_ok());
+        assert_eq!(pot_len(&vote), 1);
+        assert!(vote.flip(&players[0].id).is_ok());
+        assert_eq!(pot_len(&vote), 2);
+        assert!(vote.flip_votes.is_empty());
+    }
+
+    #[test]
+    fn test_flipping_skips_players_who_have_left() {
+        let players: Vec<Player> = ["Alice", "Bob", "Cat", "Dan"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        let pot_len = |game: &Anagrams| game.inner.read().unwrap().pot.len();
+
+        let settings = AnagramsSettings {
+            deal: DealMode::Turns,
+            ..Default::default()
+        };
+        let mut turns = Anagrams::new("testgame".to_string(), settings);
+        turns.game_state.players = players.clone();
+        assert!(turns.flip(&players[0].id).is_ok());
+        // Bob is next but has gone, so the turn passes to Cat
+        players[1].set_connected(false);
+        assert_eq!(turns.flipper().unwrap().id, players[2].id);
+        assert!(turns.flip(&players[1].id).is_err());
+        turns.tick();
+        assert_eq!(turns.flip_turn, 2);
+        assert!(turns.flip(&players[2].id).is_ok());
+        assert_eq!(turns.flipper().unwrap().id, players[3].id);
+        players[1].set_connected(true);
+
+        let settings = AnagramsSettings {
+            deal: DealMode::Vote,
+            ..Default::default()
+        };
+        let mut vote = Anagrams::new("testgame".to_string(), settings);
+        vote.game_state.players = players.clone();
+        assert!(vote.flip(&players[0].id).is_ok());
+        assert!(vote.flip(&players[1].id).is_ok());
+        assert_eq!(pot_len(&vote), 1);
+        // with Cat and Dan gone, Alice and Bob are a majority of those left
+        players[2].set_connected(false);
+        players[3].set_connected(false);
+        vote.tick();
+        assert_eq!(pot_len(&vote), 2);
+        assert!(vote.flip_votes.is_empty());
+        assert!(vote.flip(&players[0].id).is_ok());
+        assert_eq!(pot_len(&vote), 2);
+        assert!(vote.flip(&players[1].id).is_ok());
+        assert_eq!(pot_len(&vote), 3);
+    }
+
+    #[test]
+    fn test_find_steals_prefers_fewest_pot_tiles_then_fewest_words_then_others_words() {
+        let table = vec![tiles("ant"), tiles("her"), tiles("anther"), tiles("zoo")];
+        let nobody = [false; 4];
+        let steals = find_steals(&table, &nobody, &tiles("panther"), &tiles("pxy"), 3);
+        let sources: Vec<Vec<usize>> = steals.iter().map(|(words, _)| words.clone()).collect();
+        assert_eq!(sources, vec![vec![2], vec![0, 1]]);
+        assert_eq!(steals[0].1, tiles("xy"));
+
+        // the same word twice: someone else's goes before your own, then the earliest
+        let table = vec![tiles("eat"), tiles("eat"), tiles("eat")];
+        let steals = find_steals(
+            &table,
+            &[true, false, false],
+            &tiles("eats"),
+            &tiles("s"),
+            1,
+        );
+        let sources: Vec<Vec<usize>> = steals.iter().map(|(words, _)| words.clone()).collect();
+        assert_eq!(sources, vec![vec![1], vec![2], vec![0]]);
+
+        // a lone word still needs a tile from the pot
+        assert!(find_steals(&[tiles("tea")], &[false], &tiles("eat"), &tiles("x"), 3).is_empty());
+        let table = vec![tiles("ant"), tiles("her"), tiles("anther"), tiles("zoo")];
+        assert!(find_steals(&table, &nobody, &tiles("panther"), &tiles("xy"), 3).is_empty());
+        assert!(find_steals(&table[..2], &nobody, &tiles("panther"), &tiles("p"), 1).is_empty());
+    }
+
+    #[test]
+    fn test_targeted_steals_and_default_priority() {
+        let settings = AnagramsSettings {
+            steal_rules: vec![],
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let players: Vec<Player> = ["Alice", "Bob", "Cat"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        game.game_state.players = players.clone();
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            for (player, word) in players.iter().zip(["ate", "eat", "tea"]) {
+                let mut board = PlayerBoard::from_player(player);
+                board.add_word(word.to_string());
+                inner_w.players_boards.push(board);
+            }
+            inner_w.pot = tiles("trtr");
+        }
+        let words =
+            |game: &Anagrams, i: usize| game.inner.read().unwrap().players_boards[i].words.clone();
+        let target = |player: &Player, word: &str| StealTarget {
+            player_id: player.id,
+            word: word.to_string(),
+        };
+
+        // untargeted: an opponent's word before Alice's own, earliest on the table first
+        assert!(game
+            .anagram_attempt("treat".to_string(), players[0].id, &[])
+            .is_ok());
+        assert!(words(&game, 1).is_empty());
+        assert_eq!(words(&game, 2), vec!["tea"]);
+
+        assert_eq!(
+            game.anagram_attempt(
+                "treat".to_string(),
+                players[0].id,
+                &[target(&players[1], "tea")]
+            ),
+            Err("Bob doesn't have tea.".to_string())
+        );
+        assert!(game
+            .anagram_attempt(
+                "treat".to_string(),
+                players[0].id,
+                &[target(&players[2], "Tea")]
+            )
+            .is_ok());
+        assert!(words(&game, 2).is_empty());
+        assert_eq!(words(&game, 0), vec!["ate", "treat", "treat"]);
+        assert!(game
+            .anagram_attempt(
+                "great".to_string(),
+                players[0].id,
+                &[target(&players[0], "ate"), target(&players[0], "treat")]
+            )
+            .is_err());
+
+        // the refusal names the target word as typed, so it must reach the chat escaped
+        let mut rx = game.game_state.broadcast_tx.subscribe();
+        game.handle_game_socket_message(
+            serde_json::json!({
+                "kind": "anagram_attempt",
+                "data": "great",
+                "player_id": players[0].id,
+                "target": {"player_id": players[1].id, "word": "\"e\\a"},
+            })
+            .to_string(),
+        );
+        let chats: Vec<serde_json::Value> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|json| serde_json::from_str::<Message>(&json).unwrap())
+            .filter(|msg| msg.kind == "chat")
+            .map(|msg| serde_json::from_str(&msg.data).unwrap())
+            .collect();
+        assert_eq!(chats[0]["text"], "great");
+        assert_eq!(chats[1]["text"], "Bob doesn't have \"e\\a.");
+        assert_eq!(chats[1]["message_type"], "error");
+    }
+
+    /// A game where Alice has just played "eat" from the pot, with Bob, Cat and Dan watching.
+    fn challenged_game(challenge: ChallengeSettings) -> (Anagrams, Vec<Player>) {
+        let settings = AnagramsSettings {
+            steal_rules: vec![],
+            challenge,
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let players: Vec<Player> = ["Alice", "Bob", "Cat", "Dan"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        game.game_state.players = players.clone();
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            for player in &players {
+                inner_w
+                    .players_boards
+                    .push(PlayerBoard::from_player(player));
+            }
+            inner_w.pot = tiles("eats");
+        }
+        assert!(game
+            .anagram_attempt("eat".to_string(), players[0].id, &[])
+            .is_ok());
+        (game, players)
+    }
+
+    #[test]
+    fn test_challenge_quorum_counts_connected_players_only() {
+        let (mut game, players) = challenged_game(ChallengeSettings::default());
+        assert!(game.start_challenge(&players[0].id, None).is_err());
+        assert!(game.start_challenge(&players[1].id, None).is_ok());
+        assert!(game.vote_challenge(&players[0].id, false).is_err());
+        // Bob alone is not half of Bob, Cat and Dan
+        game.tick();
+        assert_eq!(game.game_state.current_state, "challenge");
+
+        players[3].set_connected(false);
+        game.tick();
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert!(game.inner.read().unwrap().players_boards[0]
+            .words
+            .is_empty());
+    }
+
+    /// Send `message` to the room's chat as `sender`, as the client does.
+    fn send_chat(game: &mut Anagrams, sender: &str, message: &str) {
+        let data = serde_json::json!({"sender": sender, "message": message}).to_string();
+        game.handle_game_socket_message(
+            serde_json::json!({"kind": "chat", "data": data}).to_string(),
+        );
+    }
+
+    #[test]
+    fn test_challenges_return_to_the_interrupted_state_and_stop_at_game_over() {
+        let (mut game, players) = challenged_game(ChallengeSettings {
+            timeout_secs: 0,
+            referee: None,
+        });
+        send_chat(&mut game, "Alice", "/pause");
+        assert_eq!(game.game_state.current_state, "paused");
+        send_chat(&mut game, "Bob", "/challenge");
+        assert_eq!(game.game_state.current_state, "challenge");
+        send_chat(&mut game, "Alice", "/pause");
+        assert_eq!(game.game_state.current_state, "challenge");
+        assert!(game.vote_challenge(&players[2].id, false).is_ok());
+        game.tick();
+        // the move stands and the game is still paused
+        assert_eq!(game.game_state.current_state, "paused");
+        assert!(game.inner.read().unwrap().paused);
+
+        send_chat(&mut game, "Alice", "/gameover");
+        assert_eq!(game.game_state.current_state, "game_over");
+        for command in ["/challenge", "/pause", "/gameover"] {
+            send_chat(&mut game, "Bob", command);
+            assert_eq!(game.game_state.current_state, "game_over");
+            assert_eq!(
+                game.game_state.chat.last().unwrap().message_type.as_deref(),
+                Some("error")
+            );
+        }
+        assert!(game.start_challenge(&players[1].id, None).is_err());
+        assert!(!game.inner.read().unwrap().active_challenge);
+    }
+
+    #[test]
+    fn test_challenge_times_out() {
+        let (mut game, players) = challenged_game(ChallengeSettings {
+            timeout_secs: 0,
+            referee: None,
+        });
+        assert!(game.start_challenge(&players[1].id, None).is_ok());
+        assert!(game.vote_challenge(&players[2].id, false).is_ok());
+        game.tick();
+        // one vote each way: the move stands
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert_eq!(
+            game.inner.read().unwrap().players_boards[0].words,
+            vec!["eat"]
+        );
+    }
+
+    #[test]
+    fn test_referee_word_list_settles_challenges() {
+        let (mut game, players) = challenged_game(ChallengeSettings {
+            timeout_secs: 30,
+            referee: Some(dictionary::DEFAULT_DICTIONARY.to_string()),
+        });
+        assert!(game.start_challenge(&players[1].id, None).is_ok());
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert_eq!(
+            game.inner.read().unwrap().players_boards[0].words,
+            vec!["eat"]
+        );
+
+        assert!(game
+            .anagram_attempt("eats".to_string(), players[2].id, &[])
+            .is_ok());
+        assert!(game.start_challenge(&players[1].id, None).is_ok());
+        let inner_r = game.inner.read().unwrap();
+        assert_eq!(inner_r.players_boards[0].words, vec!["eat"]);
+        assert!(inner_r.players_boards[2].words.is_empty());
+    }
+
+    #[test]
+    fn test_challenging_an_earlier_move_undoes_later_steals() {
+        let (mut game, players) = challenged_game(ChallengeSettings::default());
+        game.inner.write().unwrap().pot = tiles("str");
+        assert!(game
+            .anagram_attempt("eats".to_string(), players[2].id, &[])
+            .is_ok());
+        assert!(game
+            .anagram_attempt("treats".to_string(), players[1].id, &[])
+            .is_ok());
+        let ids: Vec<u64> = game
+            .inner
+            .read()
+            .unwrap()
+            .history
+            .moves()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(ids.len(), 3);
+
+        // Cat's eats survives a challenge and can't be challenged again
+        assert!(game.start_challenge(&players[0].id, Some(ids[1])).is_ok());
+        assert!(game.vote_challenge(&players[1].id, false).is_ok());
+        assert!(game.vote_challenge(&players[3].id, false).is_ok());
+        game.tick();
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert!(game.start_challenge(&players[0].id, Some(ids[1])).is_err());
+        assert!(game.start_challenge(&players[0].id, Some(99)).is_err());
+
+        // undoing Alice's eat takes back eats and treats with it
+        assert!(game.start_challenge(&players[3].id, Some(ids[0])).is_ok());
+        assert!(game.vote_challenge(&players[1].id, true).is_ok());
+        game.tick();
+        let inner_r = game.inner.read().unwrap();
+        assert!(inner_r.players_boards.iter().all(|pb| pb.words.is_empty()));
+        let mut pot = inner_r.pot.clone();
+        pot.sort();
+        assert_eq!(pot, tiles("aerstt"));
+        assert!(inner_r.history.moves().is_empty());
+    }
+
+    #[test]
+    fn test_combination_steal_and_revert() {
+        let settings = AnagramsSettings {
+            steal_rules: vec![],
+            combination_steals: true,
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let players: Vec<Player> = ["Alice", "Bob", "Cat"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        game.game_state.players = players.clone();
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            for (player, word) in players.iter().zip(["", "ant", "her"]) {
+                let mut board = PlayerBoard::from_player(player);
+                if !word.is_empty() {
+                    board.add_word(word.to_string());
+                }
+                inner_w.players_boards.push(board);
+            }
+            inner_w.pot = tiles("p");
+        }
+
+        assert!(game
+            .anagram_attempt("panther".to_string(), players[0].id, &[])
+            .is_ok());
+        {
+            let inner_r = game.inner.read().unwrap();
+            assert_eq!(inner_r.players_boards[0].words, vec!["panther"]);
+            assert!(inner_r.players_boards[1].words.is_empty());
+            assert!(inner_r.players_boards[2].words.is_empty());
+            assert_eq!(inner_r.history.latest().unwrap().victims.len(), 2);
+        }
+
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            inner_w.active_challenge = true;
+            inner_w.challenged_move = inner_w.history.latest().map(|m| m.id);
+        }
+        game.resolve_challenge(true);
+        let inner_r = game.inner.read().unwrap();
+        assert!(inner_r.players_boards[0].words.is_empty());
+        assert_eq!(inner_r.players_boards[1].words, vec!["ant"]);
+        assert_eq!(inner_r.players_boards[2].words, vec!["her"]);
+        assert_eq!(inner_r.pot, tiles("p"));
+    }
+
     #[test]
     fn test_anagram_attempt_and_sync_state_flow() {
-        // Build an Anagrams instance with a creator
-        let creator = User::new("Creator".to_string());
-        let mut game = Anagrams::new("testgame".to_string());
+        // Build an Anagrams instance
+        let mut game = Anagrams::new("testgame".to_string(), AnagramsSettings::default());
 
         // create two players
         let p1 = Player::new("Alice".to_string(), Uuid::new_v4());
@@ -1234,7 +3283,7 @@ mod tests {
             bob_board.add_word("eat".to_string());
 
             // Ensure the pot has the letters needed to form 'treat' from 'eat'
-            inner_w.pot = vec!['t', 'r'];
+            inner_w.pot = tiles("tr");
         }
 
         // Instead of calling the higher-level anagram_attempt (the player iteration
@@ -1274,24 +3323,32 @@ mod tests {
         }
 
         // Verify some_anagram returns a new pot for this steal
-        let (existing_word, maybe_new_pot, word_attempt) = {
+        let (maybe_new_pot, word_attempt) = {
             let inner_r = game.inner.read().unwrap();
             let existing_word =
                 inner_r.players_boards[victim_board_index].words[victim_word_index].clone();
             let word_attempt = "treat".to_string();
-            let maybe_new_pot = some_anagram(&existing_word, &word_attempt, inner_r.pot.clone());
-            (existing_word, maybe_new_pot, word_attempt)
+            let maybe_new_pot = some_anagram(
+                &tiles(&existing_word),
+                &tiles(&word_attempt),
+                inner_r.pot.clone(),
+            );
+            (maybe_new_pot, word_attempt)
         };
         assert!(maybe_new_pot.is_some());
         let new_pot = maybe_new_pot.unwrap();
 
         // Call sync_state to apply the transfer
+        let mut rx = game.game_state.broadcast_tx.subscribe();
+        let victim_word = {
+            let inner_r = game.inner.read().unwrap();
+            inner_r.players_boards[victim_board_index].words[victim_word_index].clone()
+        };
         game.sync_state(
             new_pot,
             &word_attempt,
-            victim_word_index,
+            &[(victim_id, victim_word)],
             &attacker_id,
-            &victim_id,
         );
 
         // After the attempt, Alice should have the new word
@@ -1313,6 +3370,174 @@ mod tests {
                 .unwrap();
             assert!(!bob_board.words.contains(&"eat".to_string()));
         }
+
+        let msg: Message = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(msg.kind, "anagram_complete");
+        let data: serde_json::Value = serde_json::from_str(&msg.data).unwrap();
+        let description = &data["description"];
+        assert_eq!(description["attacker"], "Alice");
+        assert_eq!(description["stolen"][0]["player"], "Bob");
+        assert_eq!(description["stolen"][0]["word"], "eat");
+        assert_eq!(description["pot_tiles"].as_array().unwrap().len(), 2);
+        assert!(description["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Alice took treat from Bob's eat"));
+    }
+
+    #[test]
+    fn test_hints_cost_points_and_missed_plays_are_reported() {
+        let settings = AnagramsSettings {
+            hints: HintSettings {
+                enabled: true,
+                cost: 2,
+            },
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("testgame".to_string(), settings);
+        let alice = Player::new("Alice".to_string(), Uuid::new_v4());
+        let bob = Player::new("Bob".to_string(), Uuid::new_v4());
+        game.game_state.players = vec![alice.clone(), bob.clone()];
+        game.inner.write().unwrap().pot.clear();
+        assert!(game.hint().is_err());
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            let mut alice_board = PlayerBoard::from_player(&alice);
+            alice_board.add_word("eat".to_string());
+            inner_w.players_boards.push(alice_board);
+            inner_w.players_boards.push(PlayerBoard::from_player(&bob));
+            inner_w.bag.letters = tiles("tr");
+            let (tx, _rx) = broadcast::channel(4);
+            assert!(deal_tile(&mut inner_w, &tx));
+            assert!(deal_tile(&mut inner_w, &tx));
+        }
+
+        // a tick sends the first deal to be solved now the second has replaced it
+        game.tick();
+        assert_eq!(game.deals_queued, 1);
+
+        // arett and treat tie; ties go alphabetically
+        assert_eq!(game.hint().unwrap(), "Hint: ARETT, stealing Alice's eat.");
+        // taking one over chat charges for it, but not while the hint can't reach the player
+        let _rx = game.game_state.broadcast_tx.subscribe();
+        bob.set_connected(false);
+        send_chat(&mut game, "Bob", "/hint");
+        assert_eq!(game.inner.read().unwrap().hints_used.get(&bob.id), None);
+        bob.set_connected(true);
+        send_chat(&mut game, "Bob", "/hint");
+        {
+            let inner_r = game.inner.read().unwrap();
+            let scores = game.score_breakdown(&inner_r);
+            assert_eq!(scores[1].hint_penalty, 2);
+            assert_eq!(scores[1].total, -2);
+        }
+
+        // the report is solved off the game's thread and follows game_over
+        let mut rx = game.game_state.broadcast_tx.subscribe();
+        game.end_game("Game Over!");
+        loop {
+            match rx.blocking_recv() {
+                Ok(json)
+                    if serde_json::from_str::<Message>(&json).unwrap().kind == "missed_plays" =>
+                {
+                    break
+                }
+                Err(broadcast::error::RecvError::Closed) => panic!("no missed_plays message"),
+                _ => {}
+            }
+        }
+        let missed = game.missed_report().unwrap();
+        assert_eq!(missed.len(), 2);
+        assert_eq!(missed[0].tile_number, 1);
+        let words: Vec<&str> = missed[1].plays.iter().map(|p| p.word.as_str()).collect();
+        assert_eq!(words, vec!["arett", "tater", "tetra"]);
+        assert!(missed[1].plays.iter().all(|p| p.victim == Some(alice.id)));
+        assert_eq!(missed[1].plays[0].victim, Some(alice.id));
+    }
+
+    #[test]
+    fn test_bots_play_from_the_pot_and_vote_on_challenges() {
+        let settings = AnagramsSettings {
+            steal_rules: vec![],
+            bots: vec![BotDifficulty::Perfect],
+            ..Default::default()
+        };
+        let mut game = Anagrams::new("bots".to_string(), settings);
+        let bot = game.game_state.players[0].clone();
+        assert!(bot.is_bot);
+        assert_eq!(bot.name, "Perfect Bot");
+        let players: Vec<Player> = ["Alice", "Bob", "Cat"]
+            .iter()
+            .map(|name| Player::new(name.to_string(), Uuid::new_v4()))
+            .collect();
+        game.game_state.players.extend(players.clone());
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            for player in &players {
+                inner_w
+                    .players_boards
+                    .push(PlayerBoard::from_player(player));
+            }
+            inner_w.bag.letters.clear();
+            inner_w.pot = tiles("tea");
+            inner_w.version += 1;
+        }
+
+        // nothing happens before the bot's delay has passed
+        game.tick();
+        assert!(game.inner.read().unwrap().players_boards[0]
+            .words
+            .is_empty());
+        let version = game.inner.read().unwrap().version;
+        std::thread::sleep(std::time::Duration::from_millis(1600));
+        // the solve runs on the room's solver thread, which may still be building the index
+        let started = Instant::now();
+        while game.inner.read().unwrap().players_boards[0]
+            .words
+            .is_empty()
+            && started.elapsed().as_secs() < 30
+        {
+            game.tick();
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        {
+            let inner_r = game.inner.read().unwrap();
+            assert_eq!(inner_r.players_boards[0].words.len(), 1);
+            assert!(inner_r.pot.is_empty());
+        }
+        // the table it solved is kept for the other bots until the next change
+        let (solved, plays) = game.bot_plays.lock().unwrap().clone().unwrap();
+        assert_eq!(solved, version);
+        assert!(plays.iter().all(|p| p.word.len() == 3));
+
+        // eats shares a root with eat, so the bot backs the challenge
+        {
+            let mut inner_w = game.inner.write().unwrap();
+            inner_w.players_boards[0].words = vec!["eat".to_string()];
+            inner_w.pot = tiles("s");
+        }
+        assert!(game
+            .anagram_attempt("eats".to_string(), players[0].id, &[])
+            .is_ok());
+        assert!(game.start_challenge(&players[1].id, None).is_ok());
+        game.tick();
+        assert_eq!(game.game_state.current_state, "in_progress");
+        assert_eq!(
+            game.inner.read().unwrap().players_boards[0].words,
+            vec!["eat"]
+        );
+    }
+
+    #[test]
+    fn test_define() {
+        let game = Anagrams::new("testgame".to_string(), AnagramsSettings::default());
+        assert_eq!(
+            game.define(" QI").unwrap(),
+            "QI: the vital force, in Chinese philosophy"
+        );
+        assert!(game.define("treat").unwrap().contains("no definition"));
+        assert!(game.define("zzzq").is_err());
+        assert!(game.define("").is_err());
     }
 
     #[test]
@@ -0,0 +1,161 @@
+// Computer opponents for Anagrams. A bot is an ordinary `Player` with a board. Whenever the
+// table changes it waits a delay drawn for its difficulty, then makes one of the plays the
+// solver finds, limited to the words it knows and biased towards long ones by difficulty.
+use rand::Rng;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use super::anagrams_solver::Play;
+use super::set_bot::BotDifficulty;
+
+/// How many plays a bot wants to know of to choose between.
+const CHOICES: u64 = 5;
+
+/// How a difficulty plays Anagrams.
+struct Profile {
+    /// Shortest and longest time (in seconds) the bot takes to react to a new table
+    delay: (f64, f64),
+    /// Share of the word list the bot knows, out of 100
+    vocabulary: u64,
+    /// Chance of taking the longest play it knows rather than any of them
+    long_words: f64,
+}
+
+fn profile(difficulty: BotDifficulty) -> Profile {
+    match difficulty {
+        BotDifficulty::Easy => Profile {
+            delay: (10.0, 25.0),
+            vocabulary: 20,
+            long_words: 0.2,
+        },
+        BotDifficulty::Medium => Profile {
+            delay: (6.0, 15.0),
+            vocabulary: 50,
+            long_words: 0.5,
+        },
+        BotDifficulty::Hard => Profile {
+            delay: (3.0, 8.0),
+            vocabulary: 85,
+            long_words: 0.8,
+        },
+        BotDifficulty::Perfect => Profile {
+            delay: (1.0, 1.5),
+            vocabulary: 100,
+            long_words: 1.0,
+        },
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnagramsBot {
+    pub player_id: Uuid,
+    pub difficulty: BotDifficulty,
+    /// When the bot has finished looking at the table; None once it has had its look
+    next_move_at: Option<Instant>,
+    /// The table version the bot last saw
+    seen_version: Option<u64>,
+}
+
+impl AnagramsBot {
+    pub fn new(player_id: Uuid, difficulty: BotDifficulty) -> Self {
+        AnagramsBot {
+            player_id,
+            difficulty,
+            next_move_at: None,
+            seen_version: None,
+        }
+    }
+
+    /// Note the table's version; a new one starts the bot's reaction delay again.
+    pub fn observe(&mut self, version: u64) {
+        if self.seen_version != Some(version) {
+            self.seen_version = Some(version);
+            let (min, max) = profile(self.difficulty).delay;
+            let mut rng = rand::rng();
+            // averaging two uniform draws puts most delays near the middle of the range
+            let t = (rng.random::<f64>() + rng.random::<f64>()) / 2.0;
+            self.next_move_at =
+                Some(Instant::now() + Duration::from_secs_f64(min + (max - min) * t));
+        }
+    }
+
+    /// Whether the bot has finished looking at the current table.
+    pub fn is_ready(&self) -> bool {
+        self.next_move_at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// Whether the bot knows `word`. Each bot knows a fixed slice of the list.
+    fn knows(&self, word: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (self.player_id, word).hash(&mut hasher);
+        hasher.finish() % 100 < profile(self.difficulty).vocabulary
+    }
+
+    /// How many of the longest plays the bot looks through, enough that it should know
+    /// `CHOICES` of them.
+    pub fn plays_wanted(&self) -> usize {
+        (CHOICES * 100).div_ceil(profile(self.difficulty).vocabulary) as usize
+    }
+
+    /// Pick from `plays` (longest first) and stop looking until the table changes.
+    pub fn choose(&mut self, plays: &[Play]) -> Option<Play> {
+        self.next_move_at = None;
+        let known: Vec<&Play> = plays
+            .iter()
+            .take(self.plays_wanted())
+            .filter(|p| self.knows(&p.word))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+        let mut rng = rand::rng();
+        let pick = if rng.random_bool(profile(self.difficulty).long_words) {
+            0
+        } else {
+            rng.random_range(0..known.len())
+        };
+        Some(known[pick].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(word: &str) -> Play {
+        Play {
+            word: word.to_string(),
+            victim: None,
+            stolen_word: None,
+            pot_tiles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_bots_wait_for_a_new_table_and_know_some_words() {
+        let mut bot = AnagramsBot::new(Uuid::new_v4(), BotDifficulty::Perfect);
+        assert!(!bot.is_ready());
+        bot.observe(1);
+        assert!(!bot.is_ready());
+        bot.next_move_at = Some(Instant::now());
+        assert!(bot.is_ready());
+
+        let plays = vec![play("treats"), play("treat"), play("eats")];
+        assert_eq!(bot.choose(&plays).unwrap().word, "treats");
+        // it has had its look; the same table doesn't start another
+        assert!(!bot.is_ready());
+        bot.observe(1);
+        assert!(!bot.is_ready());
+
+        let easy = AnagramsBot::new(Uuid::new_v4(), BotDifficulty::Easy);
+        assert_eq!(easy.plays_wanted(), 25);
+        assert_eq!(bot.plays_wanted(), 5);
+        let words: Vec<String> = (0..1000).map(|i| format!("word{}", i)).collect();
+        let known = words.iter().filter(|w| easy.knows(w)).count();
+        assert!(known > 100 && known < 300);
+    }
+}
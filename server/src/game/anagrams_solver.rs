@@ -0,0 +1,447 @@
+// The best plays available in a snapshot of an Anagrams table: words made from the pot alone
+// and steals of one table word plus pot tiles. Words are indexed by signature (their tiles,
+// sorted) and length, and plays are searched for longest first, each length by a lookup per
+// way of picking pot tiles or a scan of the words that long, whichever is less work. Used for
+// the missed-plays report, `/hint` and the bots.
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use super::{dictionary::Dictionary, language::LanguagePack, Uuid};
+
+const MIN_WORD_TILES: usize = 3;
+/// Lookups and words scanned before a solve settles for the plays it has found, so a huge pot
+/// can't hold up the game.
+const MAX_SOLVE_WORK: usize = 500_000;
+/// Plays reported for each deal in the missed-plays report.
+const MISSED_PER_DEAL: usize = 3;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HintSettings {
+    /// Allow `/hint`
+    pub enabled: bool,
+    /// Points a hint costs
+    pub cost: u32,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        HintSettings {
+            enabled: true,
+            cost: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct Play {
+    pub word: String,
+    /// Owner of the word stolen; None for a word from the pot alone
+    pub victim: Option<Uuid>,
+    pub stolen_word: Option<String>,
+    pub pot_tiles: Vec<String>,
+}
+
+/// The table right after a tile was dealt, and what was taken before the next one.
+#[derive(Clone, Debug)]
+pub struct DealSnapshot {
+    /// 1 for the first tile dealt
+    pub tile_number: usize,
+    pub pot: Vec<String>,
+    pub table: Vec<(Uuid, String)>,
+    pub taken: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MissedDeal {
+    pub tile_number: usize,
+    pub pot: Vec<String>,
+    /// The longest plays nobody made, best first
+    pub plays: Vec<Play>,
+}
+
+fn signature(tiles: &[String]) -> Vec<String> {
+    let mut sorted = tiles.to_vec();
+    sorted.sort();
+    sorted
+}
+
+/// Whether `tiles` is all of `base` plus some of `pot`; all three sorted.
+fn spelled_from(tiles: &[String], base: &[String], pot: &[String]) -> bool {
+    let mut base = base.iter().peekable();
+    let mut pot = pot.iter();
+    for tile in tiles {
+        if base.peek() == Some(&tile) {
+            base.next();
+        } else if !pot.by_ref().any(|p| p == tile) {
+            return false;
+        }
+    }
+    base.peek().is_none()
+}
+
+/// How many ways there are to pick `k` tiles from tiles with these counts.
+fn choices(counts: &[(String, usize)], k: usize) -> usize {
+    // coefficients of the product of (1 + x + ... + x^n) over the counts
+    let mut ways = vec![0usize; k + 1];
+    ways[0] = 1;
+    for (_, n) in counts {
+        for total in (0..=k).rev() {
+            ways[total] = (1..=(*n).min(total)).fold(ways[total], |sum, taken| {
+                sum.saturating_add(ways[total - taken])
+            });
+        }
+    }
+    ways[k]
+}
+
+/// `tiles` less `taken`, or None if `taken` isn't all in `tiles`; both sorted.
+fn sorted_difference(tiles: &[String], taken: &[String]) -> Option<Vec<String>> {
+    let mut rest = Vec::with_capacity(tiles.len());
+    let mut taken = taken.iter().peekable();
+    for tile in tiles {
+        if taken.peek() == Some(&tile) {
+            taken.next();
+        } else {
+            rest.push(tile.clone());
+        }
+    }
+    taken.peek().is_none().then_some(rest)
+}
+
+pub struct Solver {
+    language: &'static LanguagePack,
+    /// Words by signature, in buckets by their number of tiles
+    index: Vec<HashMap<Vec<String>, Vec<String>>>,
+}
+
+lazy_static! {
+    // keyed like the normalised dictionaries: (language code, list name)
+    static ref SOLVERS: RwLock<HashMap<(&'static str, String), Arc<Solver>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// The solver for `dictionary` in `language`, built on first use.
+pub fn solver_for(language: &'static LanguagePack, dictionary: &Dictionary) -> Arc<Solver> {
+    let key = (language.code, dictionary.name.clone());
+    if let Some(solver) = SOLVERS.read().unwrap().get(&key) {
+        return solver.clone();
+    }
+    let solver = Arc::new(Solver::new(language, dictionary));
+    SOLVERS.write().unwrap().insert(key, solver.clone());
+    solver
+}
+
+impl Solver {
+    pub fn new(language: &'static LanguagePack, dictionary: &Dictionary) -> Self {
+        let mut index: Vec<HashMap<Vec<String>, Vec<String>>> = Vec::new();
+        for word in dictionary.words().iter() {
+            if let Some(tiles) = language.tiles(word) {
+                if tiles.len() >= MIN_WORD_TILES {
+                    if index.len() <= tiles.len() {
+                        index.resize_with(tiles.len() + 1, HashMap::new);
+                    }
+                    index[tiles.len()]
+                        .entry(signature(&tiles))
+                        .or_default()
+                        .push(word.clone());
+                }
+            }
+        }
+        Solver { language, index }
+    }
+
+    /// Words of `len` tiles spelled by all of `base` plus tiles of `pot`, each with the pot
+    /// tiles it uses. `base` and `pot` are sorted and `counts` tallies `pot`; `work` counts the
+    /// lookups or words scanned.
+    fn extensions(
+        &self,
+        base: &[String],
+        pot: &[String],
+        counts: &[(String, usize)],
+        len: usize,
+        work: &mut usize,
+    ) -> Vec<(String, Vec<String>)> {
+        let Some(bucket) = self.index.get(len) else {
+            return Vec::new();
+        };
+        let Some(extra_len) = len.checked_sub(base.len()).filter(|&k| k > 0) else {
+            return Vec::new();
+        };
+        let ways = choices(counts, extra_len);
+        if ways <= bucket.len() {
+            *work += ways;
+            Self::look_up(bucket, base, counts, extra_len)
+        } else {
+            *work += bucket.len();
+            Self::scan(bucket, base, pot)
+        }
+    }
+
+    /// `extensions` by looking up each way of picking `extra_len` tiles from the pot.
+    fn look_up(
+        bucket: &HashMap<Vec<String>, Vec<String>>,
+        base: &[String],
+        counts: &[(String, usize)],
+        extra_len: usize,
+    ) -> Vec<(String, Vec<String>)> {
+        fn enumerate(
+            counts: &[(String, usize)],
+            left: usize,
+            extra: &mut Vec<String>,
+            visit: &mut dyn FnMut(&[String]),
+        ) {
+            let Some(((tile, n), rest)) = counts.split_first() else {
+                if left == 0 {
+                    visit(extra);
+                }
+                return;
+            };
+            let most = (*n).min(left);
+            for taken in 0..=most {
+                enumerate(rest, left - taken, extra, visit);
+                if taken < most {
+                    extra.push(tile.clone());
+                }
+            }
+            extra.truncate(extra.len() - most);
+        }
+        let mut found = Vec::new();
+        enumerate(counts, extra_len, &mut Vec::new(), &mut |extra| {
+            let mut tiles = base.to_vec();
+            tiles.extend_from_slice(extra);
+            if let Some(words) = bucket.get(&signature(&tiles)) {
+                for word in words {
+                    found.push((word.clone(), extra.to_vec()));
+                }
+            }
+        });
+        found
+    }
+
+    /// `extensions` by checking every word in the bucket.
+    fn scan(
+        bucket: &HashMap<Vec<String>, Vec<String>>,
+        base: &[String],
+        pot: &[String],
+    ) -> Vec<(String, Vec<String>)> {
+        let mut found = Vec::new();
+        for (tiles, words) in bucket {
+            if !spelled_from(tiles, base, pot) {
+                continue;
+            }
+            let extra = sorted_difference(tiles, base).unwrap_or_default();
+            for word in words {
+                found.push((word.clone(), extra.clone()));
+            }
+        }
+        found
+    }
+
+    /// The longest plays from `pot` and `table`, best first: every play of each length from
+    /// the longest down, until at least `limit` have been found. `steal_allowed(old, new)`
+    /// applies the room's steal rules.
+    pub fn solve(
+        &self,
+        pot: &[String],
+        table: &[(Uuid, String)],
+        steal_allowed: impl Fn(&str, &str) -> bool,
+        limit: usize,
+    ) -> Vec<Play> {
+        self.search(pot, table, steal_allowed, limit, &mut 0)
+    }
+
+    fn search(
+        &self,
+        pot: &[String],
+        table: &[(Uuid, String)],
+        steal_allowed: impl Fn(&str, &str) -> bool,
+        limit: usize,
+        work: &mut usize,
+    ) -> Vec<Play> {
+        let pot = signature(pot);
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for tile in &pot {
+            match counts.last_mut() {
+                Some((last, n)) if last == tile => *n += 1,
+                _ => counts.push((tile.clone(), 1)),
+            }
+        }
+        let bases: Vec<(Uuid, &String, Vec<String>)> = table
+            .iter()
+            .filter_map(|(owner, word)| {
+                Some((*owner, word, signature(&self.language.tiles(word)?)))
+            })
+            .collect();
+        let longest = bases
+            .iter()
+            .map(|(_, _, tiles)| tiles.len())
+            .max()
+            .unwrap_or(0)
+            + pot.len();
+
+        let mut plays = Vec::new();
+        for len in (MIN_WORD_TILES..=longest.min(self.index.len().saturating_sub(1))).rev() {
+            if plays.len() >= limit || *work > MAX_SOLVE_WORK {
+                break;
+            }
+            let mut found: Vec<Play> = self
+                .extensions(&[], &pot, &counts, len, work)
+                .into_iter()
+                .map(|(word, pot_tiles)| Play {
+                    word,
+                    victim: None,
+                    stolen_word: None,
+                    pot_tiles,
+                })
+                .collect();
+            for (owner, stolen, tiles) in &bases {
+                if *work > MAX_SOLVE_WORK {
+                    break;
+                }
+                for (word, pot_tiles) in self.extensions(tiles, &pot, &counts, len, work) {
+                    if steal_allowed(stolen, &word) {
+                        found.push(Play {
+                            word,
+                            victim: Some(*owner),
+                            stolen_word: Some((*stolen).clone()),
+                            pot_tiles,
+                        });
+                    }
+                }
+            }
+            found.sort_by(|a, b| (&a.word, &a.stolen_word).cmp(&(&b.word, &b.stolen_word)));
+            plays.extend(found);
+        }
+        plays
+    }
+
+    /// The longest plays `deal` had that weren't made, or None if there were none or someone
+    /// took a word at least that long anyway.
+    pub fn missed_play(
+        &self,
+        deal: &DealSnapshot,
+        steal_allowed: impl Fn(&str, &str) -> bool,
+    ) -> Option<MissedDeal> {
+        let len = |word: &str| self.language.tiles(word).map_or(0, |t| t.len());
+        let plays = self.solve(&deal.pot, &deal.table, steal_allowed, MISSED_PER_DEAL);
+        let best = len(&plays.first()?.word);
+        if deal.taken.iter().any(|word| len(word) >= best) {
+            return None;
+        }
+        let plays: Vec<Play> = plays
+            .into_iter()
+            .filter(|p| len(&p.word) == best && !deal.taken.contains(&p.word))
+            .take(MISSED_PER_DEAL)
+            .collect();
+        Some(MissedDeal {
+            tile_number: deal.tile_number,
+            pot: deal.pot.clone(),
+            plays,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{dictionary, language};
+
+    fn tiles(word: &str) -> Vec<String> {
+        word.chars().map(|c| c.to_string()).collect()
+    }
+
+    fn solver() -> Solver {
+        let dictionary = Dictionary::from_text(
+            "tiny",
+            "eat\ntea\nate\neats\nseat\ntreat\ntreats\nrat\nstar\n",
+        );
+        Solver::new(language::default_pack(), &dictionary)
+    }
+
+    #[test]
+    fn test_solve_lists_pot_words_and_steals() {
+        let solver = solver();
+        let bob = Uuid::new_v4();
+        let table = vec![(bob, "eat".to_string())];
+        let no_plain_s = |old: &str, new: &str| !(old == "eat" && new == "eats");
+        let plays = solver.solve(&tiles("rst"), &table, no_plain_s, usize::MAX);
+        let words: Vec<&str> = plays.iter().map(|p| p.word.as_str()).collect();
+        assert_eq!(words, vec!["treats", "treat", "seat"]);
+        assert_eq!(plays[0].victim, Some(bob));
+        assert_eq!(plays[0].pot_tiles, tiles("rst"));
+        // only as far down as it takes to find enough
+        let plays = solver.solve(&tiles("rst"), &table, no_plain_s, 1);
+        assert_eq!(plays.len(), 1);
+
+        let plays = solver.solve(&tiles("rats"), &[], |_, _| true, usize::MAX);
+        let words: Vec<&str> = plays.iter().map(|p| p.word.as_str()).collect();
+        assert_eq!(words, vec!["star", "rat"]);
+        assert_eq!(plays[0].victim, None);
+
+        // looking up each pick of pot tiles and scanning the bucket agree
+        let pot = tiles("rst");
+        let counts = [("r", 1), ("s", 1), ("t", 1)].map(|(t, n)| (t.to_string(), n));
+        for len in 4..=6 {
+            let mut looked_up =
+                Solver::look_up(&solver.index[len], &tiles("aet"), &counts, len - 3);
+            looked_up.sort();
+            let mut scanned = Solver::scan(&solver.index[len], &tiles("aet"), &pot);
+            scanned.sort();
+            assert!(!scanned.is_empty());
+            assert_eq!(looked_up, scanned);
+        }
+        assert_eq!(choices(&counts, 2), 3);
+        assert_eq!(choices(&[("e".to_string(), 2), ("t".to_string(), 1)], 2), 2);
+    }
+
+    #[test]
+    fn test_missed_plays_skip_deals_where_a_long_word_was_taken() {
+        let solver = solver();
+        let deal = DealSnapshot {
+            tile_number: 3,
+            pot: tiles("tea"),
+            table: vec![],
+            taken: vec![],
+        };
+        let missed = solver.missed_play(&deal, |_, _| true).unwrap();
+        assert_eq!(missed.tile_number, 3);
+        assert_eq!(missed.plays.len(), 3);
+
+        let deal = DealSnapshot {
+            tile_number: 4,
+            pot: tiles("teas"),
+            table: vec![],
+            taken: vec!["seat".to_string()],
+        };
+        assert!(solver.missed_play(&deal, |_, _| true).is_none());
+    }
+
+    #[test]
+    fn test_a_large_pot_is_solved_within_the_work_budget() {
+        let solver = solver_for(language::default_pack(), &dictionary::default_dictionary());
+        let pot = tiles("aeiourstlnaeiodgmpcbhyfkw");
+        let table: Vec<(Uuid, String)> = [
+            "eat", "tone", "ran", "stair", "crate", "lion", "seat", "rate", "note", "dog", "tear",
+            "last", "mine", "time", "road", "care", "pole", "rise", "tin", "sale",
+        ]
+        .iter()
+        .map(|word| (Uuid::new_v4(), word.to_string()))
+        .collect();
+        // a hint or a missed play stops at the longest length with a play
+        let mut work = 0;
+        let plays = solver.search(&pot, &table, |_, _| true, MISSED_PER_DEAL, &mut work);
+        assert!(plays.len() >= MISSED_PER_DEAL);
+        assert!(work < MAX_SOLVE_WORK / 2);
+        // asking for everything stops at the budget, overrunning by at most one bucket
+        let mut work = 0;
+        let plays = solver.search(&pot, &table, |_, _| true, usize::MAX, &mut work);
+        assert!(plays.len() > MISSED_PER_DEAL);
+        let biggest = solver.index.iter().map(HashMap::len).max().unwrap();
+        assert!(work <= MAX_SOLVE_WORK + biggest);
+    }
+}
@@ -0,0 +1,90 @@
+// Settling Anagrams challenges: who may vote, when a vote is decisive, what happens when
+// voting times out, and an optional referee word list that decides without a vote.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ChallengeSettings {
+    /// How long voting stays open before the votes cast so far decide
+    pub timeout_secs: u64,
+    /// Word list that settles challenges at once: the move stands if the word is in it and
+    /// shares no root with a word it stole
+    pub referee: Option<String>,
+}
+
+impl Default for ChallengeSettings {
+    fn default() -> Self {
+        ChallengeSettings {
+            timeout_secs: 30,
+            referee: None,
+        }
+    }
+}
+
+/// Counts of the votes cast by eligible players: (to revert the move, to keep it).
+fn count(votes: &HashMap<Uuid, bool>, eligible: &[Uuid]) -> (usize, usize) {
+    votes.iter().filter(|(id, _)| eligible.contains(id)).fold(
+        (0, 0),
+        |(challenge, maintain), (_, vote)| {
+            if *vote {
+                (challenge + 1, maintain)
+            } else {
+                (challenge, maintain + 1)
+            }
+        },
+    )
+}
+
+/// Some(true) once half the eligible players (rounded up) want the move reverted, Some(false)
+/// once more than half want it kept, None while undecided or if nobody can vote.
+pub fn tally(votes: &HashMap<Uuid, bool>, eligible: &[Uuid]) -> Option<bool> {
+    if eligible.is_empty() {
+        return None;
+    }
+    let (challenge, maintain) = count(votes, eligible);
+    if challenge >= eligible.len().div_ceil(2) {
+        Some(true)
+    } else if maintain > eligible.len() / 2 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// The verdict when voting times out: reverted only if more votes were cast to revert than to keep.
+pub fn timeout_verdict(votes: &HashMap<Uuid, bool>, eligible: &[Uuid]) -> bool {
+    let (challenge, maintain) = count(votes, eligible);
+    challenge > maintain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_eligible_votes_count() {
+        let players: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let eligible = &players[..4];
+        let mut votes = HashMap::new();
+        votes.insert(players[0], true);
+        // the fifth player (disconnected, or the one challenged) doesn't count
+        votes.insert(players[4], true);
+        assert_eq!(tally(&votes, eligible), None);
+        assert!(timeout_verdict(&votes, eligible));
+
+        votes.insert(players[1], true);
+        assert_eq!(tally(&votes, eligible), Some(true));
+
+        let mut votes = HashMap::new();
+        for id in &players[..3] {
+            votes.insert(*id, false);
+        }
+        assert_eq!(tally(&votes, eligible), Some(false));
+        votes.insert(players[3], true);
+        assert!(!timeout_verdict(&votes, eligible));
+        assert_eq!(tally(&votes, &[]), None);
+    }
+}
@@ -0,0 +1,106 @@
+// The daily Set puzzle: one seeded board per calendar day (UTC) and a leaderboard of
+// how long each player took to find every set on it.
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use lazy_static::lazy_static;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::Serialize;
+use std::{collections::HashMap, sync::RwLock};
+
+use super::set::{full_deck, Card};
+use super::set_solver;
+
+/// Cards on a daily puzzle board.
+pub const PUZZLE_CARDS: usize = 12;
+/// Sets hidden in every daily puzzle board.
+pub const PUZZLE_SETS: usize = 6;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DailyEntry {
+    pub player_name: String,
+    pub time_ms: i64,
+    pub completed_at: DateTime<Utc>,
+}
+
+lazy_static! {
+    static ref LEADERBOARDS: RwLock<HashMap<NaiveDate, Vec<DailyEntry>>> =
+        RwLock::new(HashMap::new());
+}
+
+pub fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+/// Seed shared by every daily puzzle dealt on `date`.
+pub fn seed_for(date: NaiveDate) -> u64 {
+    // spread consecutive days apart so neighbouring seeds don't look alike
+    (date.num_days_from_ce() as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// The 12-card board for `seed`, holding exactly six sets.
+pub fn puzzle_board(seed: u64) -> Vec<Card> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut deck = full_deck();
+    loop {
+        deck.shuffle(&mut rng);
+        let board = &deck[..PUZZLE_CARDS];
+        if set_solver::count_sets(board) == PUZZLE_SETS {
+            return board.to_vec();
+        }
+    }
+}
+
+/// Record a completion; only a player's best time for the day is kept.
+pub fn record_completion(date: NaiveDate, entry: DailyEntry) {
+    let mut boards = LEADERBOARDS.write().unwrap();
+    let entries = boards.entry(date).or_default();
+    match entries
+        .iter_mut()
+        .find(|e| e.player_name == entry.player_name)
+    {
+        Some(existing) if existing.time_ms <= entry.time_ms => {}
+        Some(existing) => *existing = entry,
+        None => entries.push(entry),
+    }
+    entries.sort_by_key(|e| e.time_ms);
+}
+
+/// Completions for `date`, fastest first.
+pub fn leaderboard(date: NaiveDate) -> Vec<DailyEntry> {
+    LEADERBOARDS
+        .read()
+        .unwrap()
+        .get(&date)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_board_is_deterministic() {
+        let seed = seed_for(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        let board = puzzle_board(seed);
+        assert_eq!(board.len(), PUZZLE_CARDS);
+        assert_eq!(set_solver::count_sets(&board), PUZZLE_SETS);
+        assert_eq!(board, puzzle_board(seed));
+    }
+
+    #[test]
+    fn test_leaderboard_keeps_best_time() {
+        let date = NaiveDate::from_ymd_opt(2001, 1, 1).unwrap();
+        let entry = |name: &str, time_ms| DailyEntry {
+            player_name: name.to_string(),
+            time_ms,
+            completed_at: Utc::now(),
+        };
+        record_completion(date, entry("Alice", 5000));
+        record_completion(date, entry("Bob", 3000));
+        record_completion(date, entry("Alice", 7000));
+        let board = leaderboard(date);
+        assert_eq!(board.len(), 2);
+        assert_eq!(board[0].player_name, "Bob");
+        assert_eq!(board[1].time_ms, 5000);
+    }
+}
@@ -0,0 +1,83 @@
+// How tiles get from the bag to the pot in an Anagrams room: on a timer, by players taking
+// turns to flip, or by flipping whenever most of the table asks for it.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DEFAULT_DEAL_INTERVAL_SECS: u64 = 7;
+/// Bounds on the timer interval a room may ask for.
+pub const MIN_DEAL_INTERVAL_SECS: u64 = 2;
+pub const MAX_DEAL_INTERVAL_SECS: u64 = 60;
+
+fn default_interval() -> u64 {
+    DEFAULT_DEAL_INTERVAL_SECS
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum DealMode {
+    /// A tile every `interval_secs` seconds
+    Timer {
+        #[serde(default = "default_interval")]
+        interval_secs: u64,
+    },
+    /// Players take turns; only the current flipper's `/flip` deals a tile
+    Turns,
+    /// `/flip` is a vote, and a tile is dealt once a majority has asked
+    Vote,
+}
+
+impl Default for DealMode {
+    fn default() -> Self {
+        DealMode::Timer {
+            interval_secs: DEFAULT_DEAL_INTERVAL_SECS,
+        }
+    }
+}
+
+impl DealMode {
+    /// Time between tiles for the timer mode, clamped to the allowed range.
+    pub fn interval(&self) -> Option<Duration> {
+        match self {
+            DealMode::Timer { interval_secs } => Some(Duration::from_secs(
+                (*interval_secs).clamp(MIN_DEAL_INTERVAL_SECS, MAX_DEAL_INTERVAL_SECS),
+            )),
+            DealMode::Turns | DealMode::Vote => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            DealMode::Timer { .. } => format!(
+                "A tile is flipped every {} seconds.",
+                self.interval().unwrap_or_default().as_secs()
+            ),
+            DealMode::Turns => "Players take turns flipping tiles with /flip.".to_string(),
+            DealMode::Vote => "A tile is flipped once most players have typed /flip.".to_string(),
+        }
+    }
+}
+
+/// Flip votes needed among `players`: more than half.
+pub fn votes_needed(players: usize) -> usize {
+    players / 2 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modes_parse_and_clamp() {
+        let timer: DealMode = serde_json::from_str(r#"{"mode": "timer"}"#).unwrap();
+        assert_eq!(timer, DealMode::default());
+        let fast: DealMode =
+            serde_json::from_str(r#"{"mode": "timer", "interval_secs": 0}"#).unwrap();
+        assert_eq!(fast.interval(), Some(Duration::from_secs(2)));
+        let turns: DealMode = serde_json::from_str(r#"{"mode": "turns"}"#).unwrap();
+        assert_eq!(turns.interval(), None);
+
+        assert_eq!(votes_needed(1), 1);
+        assert_eq!(votes_needed(2), 2);
+        assert_eq!(votes_needed(5), 3);
+    }
+}
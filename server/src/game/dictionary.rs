@@ -0,0 +1,251 @@
+// Word lists for Anagrams. The default list is compiled into the binary; more lists (or
+// replacements for the built-in one) are read from the directory named by `WORD_LIST_DIR`,
+// one word per line, each file becoming a list named after its stem (e.g. `twl.txt` -> "twl").
+// A list's definitions, for `/define`, sit beside it as `twl.defs`: a word, a tab, the definition.
+use lazy_static::lazy_static;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::Path,
+    sync::Arc,
+};
+
+/// Environment variable naming the directory extra word lists are loaded from.
+pub const WORD_LIST_DIR_VAR: &str = "WORD_LIST_DIR";
+pub const DEFAULT_DICTIONARY: &str = "collins";
+
+/// Built-in lists: name, words, definitions.
+const EMBEDDED: &[(&str, &str, &str)] = &[(
+    DEFAULT_DICTIONARY,
+    include_str!("words.txt"),
+    include_str!("definitions.txt"),
+)];
+
+/// A named word list; words are stored lowercase.
+#[derive(Clone)]
+pub struct Dictionary {
+    pub name: String,
+    words: Arc<BTreeSet<String>>,
+    /// Definitions for some of the words, keyed the same way
+    definitions: Arc<BTreeMap<String, String>>,
+}
+
+impl Dictionary {
+    pub fn from_text(name: &str, text: &str) -> Self {
+        let words = text
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .collect();
+        Dictionary {
+            name: name.to_string(),
+            words: Arc::new(words),
+            definitions: Arc::new(BTreeMap::new()),
+        }
+    }
+
+    /// Add definitions from `word<TAB>definition` lines; other lines are skipped.
+    pub fn with_definitions(mut self, text: &str) -> Self {
+        let mut definitions = (*self.definitions).clone();
+        for line in text.lines() {
+            if let Some((word, definition)) = line.split_once('\t') {
+                let word = word.trim().to_lowercase();
+                if !word.is_empty() && !word.starts_with('#') {
+                    definitions.insert(word, definition.trim().to_string());
+                }
+            }
+        }
+        self.definitions = Arc::new(definitions);
+        self
+    }
+
+    pub fn define(&self, word: &str) -> Option<&str> {
+        self.definitions.get(word).map(|d| d.as_str())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn words(&self) -> Arc<BTreeSet<String>> {
+        self.words.clone()
+    }
+
+    /// A copy of this list with `f` applied to every word.
+    pub fn map_words(&self, f: impl Fn(&str) -> String) -> Dictionary {
+        Dictionary {
+            name: self.name.clone(),
+            words: Arc::new(self.words.iter().map(|w| f(w)).collect()),
+            definitions: Arc::new(
+                self.definitions
+                    .iter()
+                    .map(|(w, d)| (f(w), d.clone()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// the word set is far too big to print
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dictionary")
+            .field("name", &self.name)
+            .field("words", &self.words.len())
+            .field("definitions", &self.definitions.len())
+            .finish()
+    }
+}
+
+struct Registry {
+    dictionaries: BTreeMap<String, Dictionary>,
+    /// Problems found while loading, reported at startup
+    errors: Vec<String>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Registry = load(std::env::var(WORD_LIST_DIR_VAR).ok().as_deref());
+}
+
+fn load(dir: Option<&str>) -> Registry {
+    let mut dictionaries = BTreeMap::new();
+    let mut errors = Vec::new();
+    for (name, text, definitions) in EMBEDDED {
+        dictionaries.insert(
+            name.to_string(),
+            Dictionary::from_text(name, text).with_definitions(definitions),
+        );
+    }
+
+    if let Some(dir) = dir {
+        match std::fs::read_dir(dir) {
+            Ok(entries) => {
+                let mut definition_files = Vec::new();
+                for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                    if path.extension().is_some_and(|ext| ext == "txt") {
+                        match load_file(&path) {
+                            Ok(dictionary) => {
+                                dictionaries.insert(dictionary.name.clone(), dictionary);
+                            }
+                            Err(e) => errors.push(e),
+                        }
+                    } else if path.extension().is_some_and(|ext| ext == "defs") {
+                        definition_files.push(path);
+                    }
+                }
+                // after the lists, so a definitions file finds its list whatever the order
+                for path in definition_files {
+                    if let Err(e) = load_definitions(&path, &mut dictionaries) {
+                        errors.push(e);
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("Cannot read word list directory {}: {}", dir, e)),
+        }
+    }
+
+    Registry {
+        dictionaries,
+        errors,
+    }
+}
+
+fn load_file(path: &Path) -> Result<Dictionary, String> {
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read word list {}: {}", path.display(), e))?;
+    let dictionary = Dictionary::from_text(&name, &text);
+    if dictionary.is_empty() {
+        return Err(format!("Word list {} has no words", path.display()));
+    }
+    Ok(dictionary)
+}
+
+fn load_definitions(
+    path: &Path,
+    dictionaries: &mut BTreeMap<String, Dictionary>,
+) -> Result<(), String> {
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read definitions {}: {}", path.display(), e))?;
+    let dictionary = dictionaries
+        .get_mut(&name)
+        .ok_or_else(|| format!("Definitions {} have no word list {}", path.display(), name))?;
+    *dictionary = dictionary.clone().with_definitions(&text);
+    Ok(())
+}
+
+/// Load every word list now and return a line per list plus any load errors, so problems
+/// show up when the server starts rather than on the first word played.
+pub fn startup_report() -> (Vec<String>, Vec<String>) {
+    let loaded = REGISTRY
+        .dictionaries
+        .values()
+        .map(|d| format!("{} ({} words)", d.name, d.len()))
+        .collect();
+    (loaded, REGISTRY.errors.clone())
+}
+
+pub fn names() -> Vec<String> {
+    REGISTRY.dictionaries.keys().cloned().collect()
+}
+
+pub fn is_installed(name: &str) -> bool {
+    REGISTRY.dictionaries.contains_key(&name.to_lowercase())
+}
+
+pub fn get(name: &str) -> Option<Dictionary> {
+    REGISTRY.dictionaries.get(&name.to_lowercase()).cloned()
+}
+
+pub fn default_dictionary() -> Dictionary {
+    get(DEFAULT_DICTIONARY).expect("the default word list is embedded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_load_from_dir_and_report_errors() {
+        let dir = std::env::temp_dir().join(format!("word-lists-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Kids.txt"), "CAT\r\ndog\n\n# comment\n").unwrap();
+        std::fs::write(dir.join("empty.txt"), "\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "not a list").unwrap();
+        std::fs::write(dir.join("kids.defs"), "CAT\ta small furry animal\nno tab\n").unwrap();
+        std::fs::write(dir.join("orphan.defs"), "dog\ta loyal animal\n").unwrap();
+
+        let registry = load(dir.to_str());
+        let kids = &registry.dictionaries["kids"];
+        assert_eq!(kids.len(), 2);
+        assert!(kids.contains("cat"));
+        assert_eq!(kids.define("cat"), Some("a small furry animal"));
+        assert_eq!(kids.define("dog"), None);
+        assert!(registry.dictionaries[DEFAULT_DICTIONARY].contains("treat"));
+        assert!(registry.dictionaries[DEFAULT_DICTIONARY]
+            .define("qi")
+            .is_some());
+        assert_eq!(registry.dictionaries.len(), 2);
+        // the empty list and the definitions with no list
+        assert_eq!(registry.errors.len(), 2);
+
+        let missing = load(Some("/nonexistent/word-lists"));
+        assert_eq!(missing.errors.len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
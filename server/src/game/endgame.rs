@@ -0,0 +1,101 @@
+// How an Anagrams game finishes once the bag is empty: a final countdown that any word taken
+// starts over, and optionally a unanimous vote to stop early.
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EndgameSettings {
+    /// Seconds of play left after the last tile is dealt; null leaves ending to `/gameover`
+    pub countdown_secs: Option<u64>,
+    /// Let the game end early once every player has typed `/end`
+    pub vote_to_end: bool,
+}
+
+impl Default for EndgameSettings {
+    fn default() -> Self {
+        EndgameSettings {
+            countdown_secs: Some(60),
+            vote_to_end: false,
+        }
+    }
+}
+
+/// The clock that runs once the bag is empty. It only runs down while the game isn't paused.
+#[derive(Clone, Debug)]
+pub struct FinalCountdown {
+    duration: Duration,
+    remaining: Duration,
+    last_tick: Instant,
+    last_announced_secs: Option<u64>,
+}
+
+impl FinalCountdown {
+    pub fn new(secs: u64, now: Instant) -> Self {
+        FinalCountdown {
+            duration: Duration::from_secs(secs),
+            remaining: Duration::from_secs(secs),
+            last_tick: now,
+            last_announced_secs: None,
+        }
+    }
+
+    /// Start the full countdown again, e.g. after a word is taken.
+    pub fn reset(&mut self) {
+        self.remaining = self.duration;
+        self.last_announced_secs = None;
+    }
+
+    /// Run the clock down by the time since the last tick, unless `paused`.
+    pub fn tick(&mut self, now: Instant, paused: bool) {
+        if !paused {
+            self.remaining = self
+                .remaining
+                .saturating_sub(now.saturating_duration_since(self.last_tick));
+        }
+        self.last_tick = now;
+    }
+
+    pub fn remaining_ms(&self) -> u64 {
+        self.remaining.as_millis() as u64
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// Remaining whole seconds, once per second; None if this second was already announced.
+    pub fn next_announcement(&mut self) -> Option<u64> {
+        let secs = self.remaining_ms().div_ceil(1000);
+        if self.last_announced_secs == Some(secs) {
+            return None;
+        }
+        self.last_announced_secs = Some(secs);
+        Some(secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_pauses_and_resets() {
+        let start = Instant::now();
+        let mut countdown = FinalCountdown::new(10, start);
+        assert_eq!(countdown.next_announcement(), Some(10));
+        assert_eq!(countdown.next_announcement(), None);
+
+        countdown.tick(start + Duration::from_secs(4), false);
+        assert_eq!(countdown.remaining_ms(), 6000);
+        countdown.tick(start + Duration::from_secs(9), true);
+        assert_eq!(countdown.remaining_ms(), 6000);
+        countdown.tick(start + Duration::from_millis(12500), false);
+        assert_eq!(countdown.next_announcement(), Some(3));
+
+        countdown.reset();
+        assert_eq!(countdown.remaining_ms(), 10000);
+        countdown.tick(start + Duration::from_secs(30), false);
+        assert!(countdown.is_expired());
+    }
+}
@@ -0,0 +1,356 @@
+// Language packs for Anagrams: which tiles go in the bag, how typed words are normalised
+// (case, accents, ß) and split into tiles, and which word list a room uses by default.
+// Tiles are strings so that digraphs such as Spanish "ll" or Dutch "ij" can be one tile.
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::RwLock};
+
+use super::dictionary::{self, Dictionary};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Accented letters that are played as their plain form.
+const STRIP_ACCENTS: &[(&str, &str)] = &[
+    ("à", "a"),
+    ("á", "a"),
+    ("â", "a"),
+    ("ä", "a"),
+    ("ç", "c"),
+    ("è", "e"),
+    ("é", "e"),
+    ("ê", "e"),
+    ("ë", "e"),
+    ("í", "i"),
+    ("î", "i"),
+    ("ï", "i"),
+    ("ó", "o"),
+    ("ô", "o"),
+    ("ö", "o"),
+    ("ù", "u"),
+    ("ú", "u"),
+    ("û", "u"),
+    ("ü", "u"),
+    ("ÿ", "y"),
+    ("æ", "ae"),
+    ("œ", "oe"),
+];
+
+/// Spanish keeps ñ but drops the acute accent and diaeresis.
+const SPANISH_ACCENTS: &[(&str, &str)] = &[
+    ("á", "a"),
+    ("é", "e"),
+    ("í", "i"),
+    ("ó", "o"),
+    ("ú", "u"),
+    ("ü", "u"),
+];
+
+/// German keeps the umlauts as their own tiles; ß is played as "ss".
+const GERMAN_LETTERS: &[(&str, &str)] = &[("ß", "ss")];
+
+#[derive(Debug)]
+pub struct LanguagePack {
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Word list used when the room doesn't pick one
+    pub dictionary: &'static str,
+    /// Each tile, how many of it go in the bag, and what it scores
+    tiles: &'static [(&'static str, u32, u32)],
+    /// Applied after lowercasing, in order
+    replacements: &'static [(&'static str, &'static str)],
+}
+
+// English uses the 143-tile word-game set; the others use their national Scrabble
+// distributions without blanks. Tile values are the national Scrabble ones throughout.
+const PACKS: &[LanguagePack] = &[
+    LanguagePack {
+        code: "en",
+        name: "English",
+        dictionary: dictionary::DEFAULT_DICTIONARY,
+        tiles: &[
+            ("a", 13, 1),
+            ("b", 3, 3),
+            ("c", 3, 3),
+            ("d", 6, 2),
+            ("e", 18, 1),
+            ("f", 3, 4),
+            ("g", 4, 2),
+            ("h", 3, 4),
+            ("i", 11, 1),
+            ("j", 2, 8),
+            ("k", 2, 5),
+            ("l", 5, 1),
+            ("m", 3, 3),
+            ("n", 8, 1),
+            ("o", 11, 1),
+            ("p", 3, 3),
+            ("q", 2, 10),
+            ("r", 9, 1),
+            ("s", 6, 1),
+            ("t", 9, 1),
+            ("u", 6, 1),
+            ("v", 3, 4),
+            ("w", 3, 4),
+            ("x", 2, 8),
+            ("y", 3, 4),
+            ("z", 2, 10),
+        ],
+        replacements: STRIP_ACCENTS,
+    },
+    LanguagePack {
+        code: "es",
+        name: "Español",
+        dictionary: "es",
+        tiles: &[
+            ("a", 12, 1),
+            ("b", 2, 3),
+            ("c", 4, 3),
+            ("ch", 1, 5),
+            ("d", 5, 2),
+            ("e", 12, 1),
+            ("f", 1, 4),
+            ("g", 2, 2),
+            ("h", 2, 4),
+            ("i", 6, 1),
+            ("j", 1, 8),
+            ("l", 4, 1),
+            ("ll", 1, 8),
+            ("m", 2, 3),
+            ("n", 5, 1),
+            ("ñ", 1, 8),
+            ("o", 9, 1),
+            ("p", 2, 3),
+            ("q", 1, 5),
+            ("r", 5, 1),
+            ("rr", 1, 8),
+            ("s", 6, 1),
+            ("t", 4, 1),
+            ("u", 5, 1),
+            ("v", 1, 4),
+            ("x", 1, 8),
+            ("y", 1, 4),
+            ("z", 1, 10),
+        ],
+        replacements: SPANISH_ACCENTS,
+    },
+    LanguagePack {
+        code: "nl",
+        name: "Nederlands",
+        dictionary: "nl",
+        tiles: &[
+            ("a", 6, 1),
+            ("b", 2, 3),
+            ("c", 2, 5),
+            ("d", 5, 2),
+            ("e", 18, 1),
+            ("f", 2, 4),
+            ("g", 3, 3),
+            ("h", 2, 4),
+            ("i", 4, 1),
+            ("ij", 2, 4),
+            ("j", 2, 4),
+            ("k", 3, 3),
+            ("l", 3, 3),
+            ("m", 3, 3),
+            ("n", 10, 1),
+            ("o", 6, 1),
+            ("p", 2, 3),
+            ("q", 1, 10),
+            ("r", 5, 2),
+            ("s", 5, 2),
+            ("t", 5, 2),
+            ("u", 3, 4),
+            ("v", 2, 4),
+            ("w", 2, 5),
+            ("x", 1, 8),
+            ("y", 1, 8),
+            ("z", 2, 4),
+        ],
+        replacements: STRIP_ACCENTS,
+    },
+    LanguagePack {
+        code: "de",
+        name: "Deutsch",
+        dictionary: "de",
+        tiles: &[
+            ("a", 5, 1),
+            ("ä", 1, 6),
+            ("b", 2, 3),
+            ("c", 2, 4),
+            ("d", 4, 1),
+            ("e", 15, 1),
+            ("f", 2, 4),
+            ("g", 3, 2),
+            ("h", 4, 2),
+            ("i", 6, 1),
+            ("j", 1, 6),
+            ("k", 2, 4),
+            ("l", 3, 2),
+            ("m", 4, 3),
+            ("n", 9, 1),
+            ("o", 3, 2),
+            ("ö", 1, 8),
+            ("p", 1, 4),
+            ("q", 1, 10),
+            ("r", 6, 1),
+            ("s", 7, 1),
+            ("t", 6, 1),
+            ("u", 6, 1),
+            ("ü", 1, 6),
+            ("v", 1, 6),
+            ("w", 1, 3),
+            ("x", 1, 8),
+            ("y", 1, 10),
+            ("z", 1, 3),
+        ],
+        replacements: GERMAN_LETTERS,
+    },
+    LanguagePack {
+        code: "fr",
+        name: "Français",
+        dictionary: "fr",
+        tiles: &[
+            ("a", 9, 1),
+            ("b", 2, 3),
+            ("c", 2, 3),
+            ("d", 3, 2),
+            ("e", 15, 1),
+            ("f", 2, 4),
+            ("g", 2, 2),
+            ("h", 2, 4),
+            ("i", 8, 1),
+            ("j", 1, 8),
+            ("k", 1, 10),
+            ("l", 5, 1),
+            ("m", 3, 2),
+            ("n", 6, 1),
+            ("o", 6, 1),
+            ("p", 2, 3),
+            ("q", 1, 8),
+            ("r", 6, 1),
+            ("s", 6, 1),
+            ("t", 6, 1),
+            ("u", 6, 1),
+            ("v", 2, 4),
+            ("w", 1, 10),
+            ("x", 1, 10),
+            ("y", 1, 10),
+            ("z", 1, 10),
+        ],
+        replacements: STRIP_ACCENTS,
+    },
+];
+
+lazy_static! {
+    /// Word lists rewritten with a pack's normalisation, keyed by (language, list name)
+    static ref NORMALIZED: RwLock<HashMap<(&'static str, String), Dictionary>> =
+        RwLock::new(HashMap::new());
+}
+
+impl LanguagePack {
+    /// Lowercase `word` and apply the pack's letter replacements.
+    pub fn normalize(&self, word: &str) -> String {
+        let mut word = word.trim().to_lowercase();
+        for (from, to) in self.replacements {
+            if word.contains(from) {
+                word = word.replace(from, to);
+            }
+        }
+        word
+    }
+
+    /// Split a normalised word into tiles, preferring digraph tiles ("llama" -> ll, a, m, a).
+    /// None if the word uses a letter the pack has no tile for.
+    pub fn tiles(&self, word: &str) -> Option<Vec<String>> {
+        let mut tiles = Vec::new();
+        let mut rest = word;
+        while !rest.is_empty() {
+            let tile = self
+                .tiles
+                .iter()
+                .map(|(tile, _, _)| *tile)
+                .filter(|tile| rest.starts_with(tile))
+                .max_by_key(|tile| tile.len())?;
+            tiles.push(tile.to_string());
+            rest = &rest[tile.len()..];
+        }
+        Some(tiles)
+    }
+
+    /// Each tile and how many of it a standard bag holds.
+    pub fn distribution(&self) -> Vec<(String, u32)> {
+        self.tiles
+            .iter()
+            .map(|(tile, count, _)| (tile.to_string(), *count))
+            .collect()
+    }
+
+    pub fn has_tile(&self, tile: &str) -> bool {
+        self.tiles.iter().any(|(t, _, _)| *t == tile)
+    }
+
+    /// What `tile` is worth under tile-value scoring.
+    pub fn value(&self, tile: &str) -> u32 {
+        self.tiles
+            .iter()
+            .find(|(t, _, _)| *t == tile)
+            .map_or(0, |(_, _, value)| *value)
+    }
+
+    /// Whether the pack's own word list is installed; rooms can't be created in it otherwise.
+    pub fn is_available(&self) -> bool {
+        dictionary::is_installed(self.dictionary)
+    }
+
+    /// Whether the lemmatizer knows the language's inflections. It is English-only, so the
+    /// shared-root checks are skipped for the others.
+    pub fn has_roots(&self) -> bool {
+        self.code == DEFAULT_LANGUAGE
+    }
+
+    /// `dictionary` with every word normalised the way this pack normalises plays, so that
+    /// e.g. "canción" in a Spanish list matches a typed "cancion".
+    pub fn normalized_dictionary(&self, dictionary: &Dictionary) -> Dictionary {
+        let key = (self.code, dictionary.name.clone());
+        if let Some(normalized) = NORMALIZED.read().unwrap().get(&key) {
+            return normalized.clone();
+        }
+        let normalized = dictionary.map_words(|word| self.normalize(word));
+        NORMALIZED.write().unwrap().insert(key, normalized.clone());
+        normalized
+    }
+}
+
+pub fn get(code: &str) -> Option<&'static LanguagePack> {
+    PACKS.iter().find(|pack| pack.code == code.to_lowercase())
+}
+
+/// Packs whose word list is installed.
+pub fn available() -> Vec<&'static LanguagePack> {
+    PACKS.iter().filter(|pack| pack.is_available()).collect()
+}
+
+pub fn default_pack() -> &'static LanguagePack {
+    get(DEFAULT_LANGUAGE).expect("the default language pack exists")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalising_and_digraph_tiles() {
+        let spanish = get("es").unwrap();
+        assert_eq!(spanish.normalize(" Canción "), "cancion");
+        assert_eq!(spanish.normalize("AÑO"), "año");
+        assert_eq!(spanish.tiles("llama").unwrap(), vec!["ll", "a", "m", "a"]);
+        assert_eq!(spanish.tiles("año").unwrap().len(), 3);
+        assert!(spanish.tiles("kiwi").is_none());
+
+        let german = get("de").unwrap();
+        assert_eq!(german.normalize("Straße"), "strasse");
+        assert_eq!(german.tiles("übel").unwrap()[0], "ü");
+
+        assert_eq!(get("nl").unwrap().tiles("ijs").unwrap(), vec!["ij", "s"]);
+        assert!(default_pack().has_tile("q"));
+    }
+}
@@ -0,0 +1,305 @@
+// Word morphology for Anagrams steals: deciding whether two words share a root, so that
+// e.g. "cat" -> "cats" or "walk" -> "walked" isn't accepted as a steal.
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Shortest base form the suffix rules will produce; shorter roots come from the table.
+const MIN_STEM: usize = 3;
+
+const IRREGULAR_FORMS: &str = include_str!("lemmas.txt");
+const ADJECTIVES: &str = include_str!("english-adjectives.txt");
+
+/// Inflectional endings and what to put back in their place, grouped by the inflection. Only
+/// the first rule in a group that leaves a word is used, so "rated" is "rate" and not "rat".
+const SUFFIX_RULES: &[&[(&str, &str)]] = &[
+    &[("ies", "y"), ("es", "e"), ("es", ""), ("s", "")],
+    &[("ied", "y"), ("ed", "e"), ("ed", "")],
+    &[("ing", "e"), ("ing", "")],
+];
+
+/// Comparative and superlative endings; only applied when the result is a known adjective,
+/// so "corner" isn't taken for a form of "corn".
+const COMPARATIVE_RULES: &[&[(&str, &str)]] = &[
+    &[("ier", "y"), ("er", "e"), ("er", "")],
+    &[("iest", "y"), ("est", "e"), ("est", "")],
+];
+
+pub trait Lemmatizer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Possible base forms of a lowercase word. Two words share a root when these overlap.
+    fn lemmas(&self, word: &str) -> Vec<String>;
+
+    fn same_lemma(&self, word1: &str, word2: &str) -> bool {
+        let first = self.lemmas(&word1.to_lowercase());
+        self.lemmas(&word2.to_lowercase())
+            .iter()
+            .any(|l| first.contains(l))
+    }
+}
+
+/// Native lemmatizer: a bundled table of irregular forms plus suffix rules checked
+/// against the word list.
+pub struct RuleLemmatizer {
+    irregular: HashMap<String, Vec<String>>,
+    /// Verbs whose past forms are all in the table, so "-ed" rules don't lead to them
+    irregular_verbs: HashSet<String>,
+    adjectives: HashSet<String>,
+    words: Arc<BTreeSet<String>>,
+}
+
+impl RuleLemmatizer {
+    /// `words` is used to discard rule-built stems that aren't words; if it's empty every
+    /// stem is accepted.
+    pub fn new(words: Arc<BTreeSet<String>>) -> Self {
+        let mut irregular: HashMap<String, Vec<String>> = HashMap::new();
+        let mut irregular_verbs = HashSet::new();
+        let mut in_verbs = false;
+        for line in IRREGULAR_FORMS.lines() {
+            let line = line.trim();
+            if let Some(section) = line.strip_prefix('#') {
+                in_verbs = section.trim() == "verbs";
+                continue;
+            }
+            let mut forms = line.split_whitespace();
+            let Some(lemma) = forms.next() else {
+                continue;
+            };
+            if in_verbs {
+                irregular_verbs.insert(lemma.to_string());
+            }
+            for form in forms {
+                irregular
+                    .entry(form.to_string())
+                    .or_default()
+                    .push(lemma.to_string());
+            }
+        }
+        let adjectives = ADJECTIVES
+            .lines()
+            .map(|l| l.trim().to_lowercase())
+            .filter(|l| !l.is_empty())
+            .collect();
+        RuleLemmatizer {
+            irregular,
+            irregular_verbs,
+            adjectives,
+            words,
+        }
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.words.is_empty() || self.words.contains(word)
+    }
+
+    /// The first stem from `rules` that `accept(suffix, stem)` takes. After a doubled
+    /// consonant the ending was added to the bare word, undoubled or not ("filled" -> "fill",
+    /// "hopped" -> "hop"), never to one ending in "e".
+    fn stem(
+        word: &str,
+        rules: &[(&str, &str)],
+        accept: impl Fn(&str, &str) -> bool,
+    ) -> Option<String> {
+        for (suffix, replacement) in rules {
+            let Some(base) = word.strip_suffix(suffix) else {
+                continue;
+            };
+            // "class" is not the plural of "clas"
+            if *suffix == "s" && base.ends_with('s') {
+                continue;
+            }
+            let mut chars = base.chars().rev();
+            let doubled = matches!((chars.next(), chars.next()),
+                (Some(last), Some(prev)) if last == prev && !"aeiou".contains(last));
+            let mut candidates = Vec::new();
+            if replacement.is_empty() {
+                candidates.push(base.to_string());
+                if doubled {
+                    let mut undoubled = base.to_string();
+                    undoubled.pop();
+                    candidates.push(undoubled);
+                }
+            } else if !(doubled && *replacement == "e") {
+                candidates.push(format!("{}{}", base, replacement));
+            }
+            if let Some(stem) = candidates
+                .into_iter()
+                .find(|s| s.chars().count() >= MIN_STEM && accept(suffix, s))
+            {
+                return Some(stem);
+            }
+        }
+        None
+    }
+}
+
+impl Lemmatizer for RuleLemmatizer {
+    fn name(&self) -> &'static str {
+        "built-in"
+    }
+
+    fn lemmas(&self, word: &str) -> Vec<String> {
+        let mut lemmas = vec![word.to_string()];
+        if let Some(irregular) = self.irregular.get(word) {
+            lemmas.extend(irregular.iter().cloned());
+        }
+        for rules in SUFFIX_RULES {
+            // "seed" isn't "see" + "d": an irregular verb's past forms are in the table
+            lemmas.extend(Self::stem(word, rules, |suffix, stem| {
+                self.is_word(stem)
+                    && !(suffix.ends_with("ed") && self.irregular_verbs.contains(stem))
+            }));
+        }
+        for rules in COMPARATIVE_RULES {
+            lemmas.extend(Self::stem(word, rules, |_, stem| {
+                self.adjectives.contains(stem)
+            }));
+        }
+        lemmas.dedup();
+        lemmas
+    }
+}
+
+#[cfg(feature = "spacy")]
+mod spacy {
+    use super::Lemmatizer;
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    /// spaCy's `en_core_web_sm` lemmatizer, called through PyO3.
+    pub struct SpacyLemmatizer {
+        nlp: Py<PyAny>,
+    }
+
+    impl SpacyLemmatizer {
+        pub fn load() -> Result<Self, String> {
+            Python::attach(|py| {
+                // pick up packages from an activated virtualenv
+                if let Ok(venv) = std::env::var("VIRTUAL_ENV") {
+                    let sys_path = py
+                        .import("sys")
+                        .and_then(|sys| sys.getattr("path"))
+                        .map_err(|e| e.to_string())?;
+                    let lib = std::path::Path::new(&venv).join("lib");
+                    for entry in std::fs::read_dir(lib).into_iter().flatten().flatten() {
+                        let site_packages = entry.path().join("site-packages");
+                        if site_packages.is_dir() {
+                            let _ = sys_path
+                                .call_method1("insert", (0, site_packages.to_string_lossy()));
+                        }
+                    }
+                }
+
+                let spacy = py.import("spacy").map_err(|e| e.to_string())?;
+                let kwargs = PyDict::new(py);
+                // exclude heavy pipeline components not needed
+                kwargs
+                    .set_item("exclude", vec!["parser", "ner"])
+                    .map_err(|e| e.to_string())?;
+                let nlp = spacy
+                    .call_method("load", ("en_core_web_sm",), Some(&kwargs))
+                    .map_err(|e| e.to_string())?;
+                Ok(SpacyLemmatizer { nlp: nlp.unbind() })
+            })
+        }
+    }
+
+    impl Lemmatizer for SpacyLemmatizer {
+        fn name(&self) -> &'static str {
+            "spaCy"
+        }
+
+        fn lemmas(&self, word: &str) -> Vec<String> {
+            Python::attach(|py| {
+                let nlp = self.nlp.bind(py);
+                let lemma = (|| -> PyResult<String> {
+                    let doc = nlp.call1((word,))?;
+                    doc.get_item(0)?.getattr("lemma_")?.extract()
+                })();
+                vec![lemma.unwrap_or_else(|_| word.to_string())]
+            })
+        }
+    }
+}
+
+/// The lemmatizer used for steal checks: spaCy when built with the `spacy` feature and it
+/// loads, otherwise the built-in rules.
+pub fn default_lemmatizer(words: Arc<BTreeSet<String>>) -> Box<dyn Lemmatizer> {
+    #[cfg(feature = "spacy")]
+    match spacy::SpacyLemmatizer::load() {
+        Ok(lemmatizer) => return Box::new(lemmatizer),
+        Err(e) => eprintln!(
+            "Could not load spaCy ({}); using the built-in lemmatizer",
+            e
+        ),
+    }
+    Box::new(RuleLemmatizer::new(words))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lemmatizer() -> RuleLemmatizer {
+        let words = [
+            "cat", "walk", "run", "corn", "corner", "class", "box", "pass", "passe", "fill",
+            "fille", "hat", "hate", "pan", "pane", "see", "seed", "not", "note", "rat", "rate",
+            "win", "wine", "tap", "tape", "cap", "cape", "rob", "robe", "hop", "hope", "bar",
+            "bare", "burn",
+        ]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+        RuleLemmatizer::new(Arc::new(words))
+    }
+
+    #[test]
+    fn test_rules_check_stems_against_word_list() {
+        let lemmatizer = lemmatizer();
+        assert!(lemmatizer.same_lemma("cats", "cat"));
+        assert!(lemmatizer.same_lemma("boxes", "box"));
+        assert!(lemmatizer.same_lemma("running", "run"));
+        assert!(lemmatizer.same_lemma("Walked", "WALK"));
+        assert!(lemmatizer.same_lemma("went", "goes"));
+        assert!(lemmatizer.same_lemma("biggest", "bigger"));
+        assert!(!lemmatizer.same_lemma("corner", "corn"));
+        assert!(!lemmatizer.same_lemma("class", "clas"));
+    }
+
+    #[test]
+    fn test_rules_pick_one_stem_per_ending() {
+        let lemmatizer = lemmatizer();
+        for (word, form) in [
+            ("hat", "hated"),
+            ("pan", "panes"),
+            ("see", "seed"),
+            ("not", "notes"),
+            ("rat", "rated"),
+            ("win", "wines"),
+            ("tap", "taped"),
+            ("cap", "capes"),
+            ("rob", "robes"),
+            ("hop", "hoping"),
+            ("bar", "bares"),
+            ("passe", "passes"),
+            ("fille", "filled"),
+        ] {
+            assert!(!lemmatizer.same_lemma(word, form), "{} / {}", word, form);
+        }
+        for (word, form) in [
+            ("hate", "hated"),
+            ("pane", "panes"),
+            ("see", "seeing"),
+            ("see", "seen"),
+            ("hope", "hoping"),
+            ("hop", "hopping"),
+            ("tap", "tapped"),
+            ("pass", "passes"),
+            ("fill", "filled"),
+            ("burn", "burned"),
+            ("burn", "burnt"),
+        ] {
+            assert!(lemmatizer.same_lemma(word, form), "{} / {}", word, form);
+        }
+    }
+}
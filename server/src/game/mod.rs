@@ -1,12 +1,30 @@
 // Game trait is synchronous to keep implementations simple and avoid requiring async_trait
 use serde::Serialize;
 use std::sync::Arc;
-use tokio;
 use uuid::Uuid;
 
 pub mod anagrams;
+pub mod anagrams_bot;
+pub mod anagrams_solver;
+pub mod challenge;
+pub mod daily;
+pub mod deal_mode;
+pub mod dictionary;
+pub mod endgame;
+pub mod language;
+pub mod lemma;
+pub mod move_history;
 pub mod player;
+pub mod practice;
+pub mod round;
 pub mod set;
+pub mod set_analysis;
+pub mod set_bot;
+pub mod set_solver;
+pub mod steal_rules;
+pub mod teams;
+pub mod tile_bag;
+pub mod word_scoring;
 
 #[derive(Clone, Serialize, Debug)]
 pub struct ChatMessage {
@@ -32,7 +50,11 @@ impl GameList {
     }
 
     pub fn list_games(&self) -> Vec<GameState> {
-        self.games.iter().map(|g| g.copy_details()).collect()
+        self.games
+            .iter()
+            .map(|g| g.copy_details())
+            .filter(|g| g.listed)
+            .collect()
     }
 
     pub fn remove_game(&mut self, index: usize) {
@@ -61,6 +83,12 @@ pub trait Game: Send + Sync {
     fn copy_details(&self) -> GameState;
     fn get_details(&self) -> &GameState;
     fn handle_game_socket_message(&mut self, txt: String);
+    /// Advance time-driven logic (bots, clocks); called a few times a second by the router.
+    fn tick(&mut self) {}
+    /// Post-game analysis, once the game has finished and if the game type supports it.
+    fn analysis(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 #[derive(Clone, Serialize, Debug)]
@@ -72,4 +100,7 @@ pub struct GameState {
     #[serde(skip)] // don't attempt to (de)serialize this non-serializable shared state
     pub broadcast_tx: Arc<tokio::sync::broadcast::Sender<String>>,
     pub chat: Vec<ChatMessage>,
+    // unlisted rooms are reachable by id but left out of the lobby's games list
+    #[serde(skip)]
+    pub listed: bool,
 }
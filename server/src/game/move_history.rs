@@ -0,0 +1,133 @@
+// Recent Anagrams moves, kept so that any of them can be challenged. Undoing a move also
+// undoes every later move that was built on the word it made.
+use serde::Serialize;
+use std::collections::VecDeque;
+
+use super::Uuid;
+
+/// How many recent moves stay open to challenge.
+pub const MAX_HISTORY: usize = 20;
+
+/// Words taken in a steal, each with the player it was taken from.
+pub type Victims = Vec<(Uuid, String)>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Move {
+    pub id: u64,
+    pub attacker_id: Uuid,
+    pub word_taken: String,
+    /// Empty if the word came from the pot alone
+    pub victims: Victims,
+    /// Tiles the move took from the pot
+    pub pot_tiles: Vec<String>,
+    /// The move survived a challenge and can't be challenged again
+    pub upheld: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MoveHistory {
+    moves: VecDeque<Move>,
+    next_id: u64,
+}
+
+impl MoveHistory {
+    /// Record a move, forgetting the oldest once the history is full; returns the move's id.
+    pub fn record(
+        &mut self,
+        attacker_id: Uuid,
+        word_taken: &str,
+        victims: Victims,
+        pot_tiles: Vec<String>,
+    ) -> u64 {
+        self.next_id += 1;
+        self.moves.push_back(Move {
+            id: self.next_id,
+            attacker_id,
+            word_taken: word_taken.to_string(),
+            victims,
+            pot_tiles,
+            upheld: false,
+        });
+        if self.moves.len() > MAX_HISTORY {
+            self.moves.pop_front();
+        }
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Move> {
+        self.moves.iter().find(|m| m.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Move> {
+        self.moves.iter_mut().find(|m| m.id == id)
+    }
+
+    pub fn latest(&self) -> Option<&Move> {
+        self.moves.back()
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        self.moves.iter().cloned().collect()
+    }
+
+    /// The moves to undo, newest first, to take back move `id`: the move itself and every
+    /// later move that stole the word it made, or a word made from that, and so on.
+    pub fn reversal_plan(&self, id: u64) -> Option<Vec<Move>> {
+        let start = self.moves.iter().position(|m| m.id == id)?;
+        let challenged = &self.moves[start];
+        let mut plan = vec![challenged.clone()];
+        // words on the table that only exist because of the moves in the plan
+        let mut dependent: Victims = vec![(challenged.attacker_id, challenged.word_taken.clone())];
+        for later in self.moves.iter().skip(start + 1) {
+            let mut depends = false;
+            for victim in &later.victims {
+                if let Some(i) = dependent.iter().position(|d| d == victim) {
+                    dependent.remove(i);
+                    depends = true;
+                }
+            }
+            if depends {
+                plan.push(later.clone());
+                dependent.push((later.attacker_id, later.word_taken.clone()));
+            }
+        }
+        plan.reverse();
+        Some(plan)
+    }
+
+    pub fn remove(&mut self, ids: &[u64]) {
+        self.moves.retain(|m| !ids.contains(&m.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reversal_follows_later_steals() {
+        let (alice, bob, cat) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut history = MoveHistory::default();
+        let eat = history.record(alice, "eat", vec![], vec![]);
+        let dog = history.record(cat, "dog", vec![], vec![]);
+        let treat = history.record(bob, "treat", vec![(alice, "eat".into())], vec![]);
+        history.record(cat, "gods", vec![(cat, "dog".into())], vec![]);
+        let threat = history.record(alice, "threat", vec![(bob, "treat".into())], vec![]);
+
+        let plan: Vec<u64> = history
+            .reversal_plan(eat)
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(plan, vec![threat, treat, eat]);
+        assert_eq!(history.reversal_plan(dog).unwrap().len(), 2);
+        assert_eq!(history.reversal_plan(threat).unwrap().len(), 1);
+
+        for _ in 0..MAX_HISTORY {
+            history.record(cat, "cat", vec![], vec![]);
+        }
+        assert!(history.get(eat).is_none());
+        assert!(history.reversal_plan(threat).is_none());
+    }
+}
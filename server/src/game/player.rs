@@ -12,6 +12,9 @@ pub struct Player {
     pub name: String,
     pub score: u32,
     pub id: Uuid,
+    // computer-controlled players are shown as such and kept out of leaderboards and records
+    #[serde(default)]
+    pub is_bot: bool,
     // atomic flag that indicates whether this player is currently connected
     #[serde(skip)] // don't attempt to (de)serialize this non-serializable shared state
     pub connected: Arc<AtomicBool>,
@@ -23,15 +26,24 @@ impl Player {
             name,
             id,
             score: 0,
+            is_bot: false,
             connected: AtomicBool::new(true).into(),
         }
     }
 
+    pub fn bot(name: String) -> Self {
+        Player {
+            is_bot: true,
+            ..Player::new(name, Uuid::new_v4())
+        }
+    }
+
     pub fn from_user(user: &user::User) -> Self {
         Player {
             name: user.name.clone(),
             id: user.id,
             score: 0,
+            is_bot: false,
             connected: AtomicBool::new(true).into(),
         }
     }
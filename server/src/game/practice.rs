@@ -0,0 +1,130 @@
+// Solo Set practice: per-session timing and each player's personal bests.
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::{collections::HashMap, sync::RwLock};
+
+/// What a practice run is timed over.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case", tag = "format")]
+pub enum PracticeFormat {
+    FullDeck,
+    Sprint { sets: u32 },
+}
+
+/// Timing for one solo run.
+#[derive(Clone, Debug, Serialize)]
+pub struct PracticeSession {
+    pub format: PracticeFormat,
+    pub started: DateTime<Utc>,
+    /// When the board last changed; reaction times are measured from here
+    #[serde(skip)]
+    board_shown: DateTime<Utc>,
+    pub reaction_ms: Vec<i64>,
+    pub wrong_attempts: u32,
+    pub total_ms: Option<i64>,
+    pub personal_best_ms: Option<i64>,
+    pub new_personal_best: bool,
+}
+
+lazy_static! {
+    static ref PERSONAL_BESTS: RwLock<HashMap<(String, PracticeFormat), i64>> =
+        RwLock::new(HashMap::new());
+}
+
+impl PracticeSession {
+    pub fn new(format: PracticeFormat, player_name: &str) -> Self {
+        let now = Utc::now();
+        PracticeSession {
+            format,
+            started: now,
+            board_shown: now,
+            reaction_ms: Vec::new(),
+            wrong_attempts: 0,
+            total_ms: None,
+            personal_best_ms: personal_best(player_name, format),
+            new_personal_best: false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.total_ms.is_some()
+    }
+
+    /// Record a find and return true once a sprint has reached its target.
+    pub fn record_find(&mut self) -> bool {
+        let now = Utc::now();
+        self.reaction_ms
+            .push((now - self.board_shown).num_milliseconds());
+        self.board_shown = now;
+        match self.format {
+            PracticeFormat::Sprint { sets } => self.reaction_ms.len() as u32 >= sets,
+            PracticeFormat::FullDeck => false,
+        }
+    }
+
+    pub fn record_wrong_attempt(&mut self) {
+        self.wrong_attempts += 1;
+    }
+
+    /// Stop the clock and update `player_name`'s personal best.
+    pub fn finish(&mut self, player_name: &str) {
+        if self.is_finished() {
+            return;
+        }
+        let total = (Utc::now() - self.started).num_milliseconds();
+        self.total_ms = Some(total);
+        self.new_personal_best = record_personal_best(player_name, self.format, total);
+        if self.new_personal_best {
+            self.personal_best_ms = Some(total);
+        }
+    }
+
+    pub fn average_reaction_ms(&self) -> Option<i64> {
+        if self.reaction_ms.is_empty() {
+            return None;
+        }
+        Some(self.reaction_ms.iter().sum::<i64>() / self.reaction_ms.len() as i64)
+    }
+}
+
+pub fn personal_best(player_name: &str, format: PracticeFormat) -> Option<i64> {
+    PERSONAL_BESTS
+        .read()
+        .unwrap()
+        .get(&(player_name.to_string(), format))
+        .copied()
+}
+
+/// Returns true if `total_ms` beats the stored record (or is the first one).
+fn record_personal_best(player_name: &str, format: PracticeFormat, total_ms: i64) -> bool {
+    let mut bests = PERSONAL_BESTS.write().unwrap();
+    let best = bests
+        .entry((player_name.to_string(), format))
+        .or_insert(i64::MAX);
+    if total_ms < *best {
+        *best = total_ms;
+        return true;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprint_finishes_after_target() {
+        let mut session = PracticeSession::new(PracticeFormat::Sprint { sets: 2 }, "Sprinter");
+        assert!(!session.record_find());
+        assert!(session.record_find());
+        session.finish("Sprinter");
+        assert!(session.is_finished());
+        assert!(session.new_personal_best);
+        assert_eq!(
+            personal_best("Sprinter", PracticeFormat::Sprint { sets: 2 }),
+            session.total_ms
+        );
+        assert_eq!(personal_best("Sprinter", PracticeFormat::FullDeck), None);
+    }
+}
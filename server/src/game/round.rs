@@ -0,0 +1,122 @@
+// Fixed-length Set rounds: "most sets in N minutes" with a server-side clock.
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How a tie for first place is settled when the clock runs out.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// Everyone on the top score wins
+    #[default]
+    Shared,
+    /// Whoever reached the top score first wins
+    FirstToReach,
+    /// Play on; the next set found by one of the tied players wins
+    SuddenDeath,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimedRound {
+    pub duration_secs: u64,
+    pub ends_at: DateTime<Utc>,
+    pub tie_break: TieBreak,
+    pub sudden_death: bool,
+    #[serde(skip)]
+    last_announced_secs: Option<i64>,
+}
+
+/// A player's standing at the end of a round.
+pub struct Standing {
+    pub id: Uuid,
+    pub score: u32,
+    /// When the player last scored; None if they never did
+    pub last_scored: Option<DateTime<Utc>>,
+}
+
+impl TimedRound {
+    pub fn new(duration_secs: u64, tie_break: TieBreak) -> Self {
+        TimedRound {
+            duration_secs,
+            ends_at: Utc::now() + Duration::seconds(duration_secs as i64),
+            tie_break,
+            sudden_death: false,
+            last_announced_secs: None,
+        }
+    }
+
+    pub fn remaining_ms(&self) -> i64 {
+        (self.ends_at - Utc::now()).num_milliseconds().max(0)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining_ms() == 0
+    }
+
+    /// Remaining whole seconds, once per second; None if this second was already announced.
+    pub fn next_announcement(&mut self) -> Option<i64> {
+        let secs = (self.remaining_ms() + 999) / 1000;
+        if self.last_announced_secs == Some(secs) {
+            return None;
+        }
+        self.last_announced_secs = Some(secs);
+        Some(secs)
+    }
+
+    /// Winners under the round's tie-break rule. An empty result means the round goes to
+    /// sudden death and `sudden_death` is switched on.
+    pub fn decide(&mut self, standings: &[Standing]) -> Vec<Uuid> {
+        let Some(top) = standings.iter().map(|s| s.score).max() else {
+            return Vec::new();
+        };
+        let leaders: Vec<&Standing> = standings.iter().filter(|s| s.score == top).collect();
+        if leaders.len() == 1 {
+            return vec![leaders[0].id];
+        }
+        match self.tie_break {
+            TieBreak::Shared => leaders.iter().map(|s| s.id).collect(),
+            TieBreak::FirstToReach => leaders
+                .iter()
+                .min_by_key(|s| s.last_scored)
+                .map(|s| vec![s.id])
+                .unwrap_or_default(),
+            TieBreak::SuddenDeath => {
+                self.sudden_death = true;
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(score: u32, secs_ago: Option<i64>) -> Standing {
+        Standing {
+            id: Uuid::new_v4(),
+            score,
+            last_scored: secs_ago.map(|s| Utc::now() - Duration::seconds(s)),
+        }
+    }
+
+    #[test]
+    fn test_tie_breaks() {
+        let standings = vec![
+            standing(3, Some(10)),
+            standing(3, Some(50)),
+            standing(1, Some(5)),
+        ];
+
+        let mut round = TimedRound::new(60, TieBreak::Shared);
+        assert_eq!(round.decide(&standings).len(), 2);
+
+        let mut round = TimedRound::new(60, TieBreak::FirstToReach);
+        assert_eq!(round.decide(&standings), vec![standings[1].id]);
+
+        let mut round = TimedRound::new(60, TieBreak::SuddenDeath);
+        assert!(round.decide(&standings).is_empty());
+        assert!(round.sudden_death);
+        assert_eq!(round.decide(&standings[1..]), vec![standings[1].id]);
+    }
+}
@@ -1,25 +1,175 @@
 use crate::{game::GameState, user::User};
-use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::{collections::HashMap, sync::Arc};
 
 use super::Uuid;
-use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
-use crate::router::Message;
+use crate::router::{DirectMessage, Message};
+
+use super::daily::{self, DailyEntry};
+use super::practice::{PracticeFormat, PracticeSession};
+use super::round::{Standing, TieBreak, TimedRound};
+use super::set_analysis;
+use super::set_bot::{BotDifficulty, SetBot};
+use super::set_solver::{self, BoardStats};
+use super::teams::{self, CoopProgress, Scoring, Team};
+use super::Game;
 
 use tokio::sync::broadcast;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Card {
     pub array: [u8; 4],
 }
 
+/// How a room hands out hints.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "policy")]
+pub enum HintPolicy {
+    Disabled,
+    #[default]
+    Free,
+    /// Each hint takes a point off the requester's score
+    CostsPoint,
+    /// Each player may request at most `max_hints` hints per game
+    Limited {
+        max_hints: u32,
+    },
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SetMode {
+    #[default]
+    Classic,
+    /// Today's shared 12-card board: every player races to find all six sets on it
+    DailyPuzzle,
+    /// An unlisted single-player room played against the clock
+    Practice,
+}
+
+/// Room settings chosen when a Set game is created.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetSettings {
+    pub hints: HintPolicy,
+    pub mode: SetMode,
+    /// Shuffle seed; a random one is picked when omitted
+    pub seed: Option<u64>,
+    /// In practice mode, stop after this many sets instead of clearing the deck
+    pub sprint_sets: Option<u32>,
+    /// Bots seated when the room is created (classic mode only)
+    pub bots: Vec<BotDifficulty>,
+    pub scoring: Scoring,
+    /// Play a fixed-length round instead of until the deck runs out
+    pub round_secs: Option<u64>,
+    pub tie_break: TieBreak,
+    /// Classic table rules: the board stays at 12 until a player calls "no set"
+    pub manual_deal: bool,
+}
+
+/// Progress of each player through a daily puzzle.
+#[derive(Clone, Debug, Serialize)]
+pub struct DailyPuzzle {
+    pub date: NaiveDate,
+    #[serde(skip)]
+    started: HashMap<Uuid, DateTime<Utc>>,
+    #[serde(skip)]
+    found: HashMap<Uuid, Vec<[Card; 3]>>,
+}
+
+#[derive(Serialize)]
+struct PuzzleProgressData {
+    found: Vec<[Card; 3]>,
+    sets_total: usize,
+    time_ms: Option<i64>,
+}
+
+/// Per-player hint state: the set being revealed and how many of its cards were shown.
+#[derive(Clone, Debug, Default, Serialize)]
+struct HintProgress {
+    target: Option<[Card; 3]>,
+    revealed: usize,
+    used: u32,
+}
+
+/// Something that happened during a Set game, kept in order for later analysis.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "event")]
+pub enum SetEvent {
+    /// A new board was laid out (at the start, or after cards were replaced or added)
+    Deal {
+        at: DateTime<Utc>,
+        board: Vec<Card>,
+        stats: BoardStats,
+    },
+    Find {
+        at: DateTime<Utc>,
+        player_id: Uuid,
+        cards: [Card; 3],
+        /// Time since the board was dealt
+        reaction_ms: i64,
+    },
+    WrongAttempt {
+        at: DateTime<Utc>,
+        player_id: Uuid,
+        cards: [Card; 3],
+    },
+    NoSetCall {
+        at: DateTime<Utc>,
+        player_id: Uuid,
+        correct: bool,
+    },
+    GameOver {
+        at: DateTime<Utc>,
+    },
+}
+
+/// Running totals for one player, reported when the game ends.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PlayerStats {
+    pub sets_found: u32,
+    /// Quickest find, measured from when the board was dealt
+    pub fastest_find_ms: Option<i64>,
+    pub wrong_attempts: u32,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct Set {
     pub game_state: super::GameState,
     pub deck: Vec<Card>,  // Placeholder for actual card representation
     pub board: Vec<Card>, // Cards currently on the board
     pub previous_set: Option<Vec<Card>>, // Last found set
+    pub settings: SetSettings,
+    pub seed: u64,
+    pub puzzle: Option<DailyPuzzle>,
+    pub practice: Option<PracticeSession>,
+    pub teams: Vec<Team>,
+    pub coop: Option<CoopProgress>,
+    pub round: Option<TimedRound>,
+    pub winners: Vec<Uuid>,
+    pub events: Vec<SetEvent>,
+    #[serde(skip)]
+    hints: HashMap<Uuid, HintProgress>,
+    #[serde(skip)]
+    bots: Vec<SetBot>,
+    #[serde(skip)]
+    last_scored: HashMap<Uuid, DateTime<Utc>>,
+    #[serde(skip)]
+    player_stats: HashMap<Uuid, PlayerStats>,
+    #[serde(skip)]
+    board_dealt_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct HintData {
+    // board indices and cards of the revealed part of a valid set
+    indices: Vec<usize>,
+    cards: Vec<Card>,
+    hints_used: u32,
+    hints_remaining: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -30,6 +180,53 @@ struct SetFoundData {
     previous_set: Option<Vec<Card>>,
     finder_name: Option<String>,
     chat: Vec<super::ChatMessage>,
+    board_stats: BoardStats,
+    practice: Option<PracticeSession>,
+    teams: Vec<Team>,
+    coop: Option<CoopProgress>,
+}
+
+#[derive(Serialize)]
+struct Ranking {
+    // players on the same score share a rank
+    rank: usize,
+    player_id: Uuid,
+    name: String,
+    score: u32,
+    is_bot: bool,
+    #[serde(flatten)]
+    stats: PlayerStats,
+}
+
+#[derive(Serialize)]
+struct GameOverData {
+    game_state: GameState,
+    chat: Vec<super::ChatMessage>,
+    rankings: Vec<Ranking>,
+    cards_left: Vec<Card>,
+    teams: Vec<Team>,
+    // None when no teams are playing or the top teams are tied
+    winning_team: Option<String>,
+    coop: Option<CoopProgress>,
+    winners: Vec<String>,
+    tie_break: Option<TieBreak>,
+}
+
+#[derive(Serialize)]
+struct NoSetResultData {
+    game_state: GameState,
+    board: Vec<Card>,
+    chat: Vec<super::ChatMessage>,
+    caller_name: String,
+    correct: bool,
+    // sets that were on the board when a wrong call was made
+    sets_available: usize,
+}
+
+#[derive(Serialize)]
+struct ClockData {
+    remaining_ms: i64,
+    sudden_death: bool,
 }
 
 #[derive(Serialize)]
@@ -39,10 +236,21 @@ struct GameStateData {
     board: Vec<Card>,
     previous_set: Option<Vec<Card>>,
     chat: Vec<super::ChatMessage>,
+    settings: SetSettings,
+    board_stats: BoardStats,
+    seed: u64,
+    puzzle: Option<DailyPuzzle>,
+    daily_leaderboard: Option<Vec<DailyEntry>>,
+    practice: Option<PracticeSession>,
+    teams: Vec<Team>,
+    coop: Option<CoopProgress>,
+    round: Option<TimedRound>,
+    // lets late joiners start their countdown from the server's clock
+    remaining_ms: Option<i64>,
 }
 
-fn deal_cards() -> (Vec<Card>, Vec<Card>) {
-    // Placeholder for dealing cards logic
+/// All 81 cards in a fixed order.
+pub fn full_deck() -> Vec<Card> {
     let mut cards = Vec::with_capacity(81);
     for i in 0..3 {
         for j in 0..3 {
@@ -55,62 +263,140 @@ fn deal_cards() -> (Vec<Card>, Vec<Card>) {
             }
         }
     }
-    cards.shuffle(&mut rand::rng());
-    (cards.split_off(12), cards)
+    cards
 }
 
-fn check_attribute(cards: &[u8; 3]) -> bool {
-    let sum: u8 = cards.iter().sum();
-    sum % 3 == 0
+fn deal_cards(rng: &mut impl Rng) -> (Vec<Card>, Vec<Card>) {
+    let mut cards = full_deck();
+    cards.shuffle(rng);
+    (cards.split_off(12), cards)
 }
 
-fn check_set(cards: &[Card; 3]) -> bool {
-    for field in 0..4 {
-        let attrs = [
-            cards[0].array[field],
-            cards[1].array[field],
-            cards[2].array[field],
-        ];
-        if !check_attribute(&attrs) {
-            return false;
-        }
-    }
-    return true;
+/// The same three cards regardless of the order they were picked in.
+fn normalized_set(cards: &[Card; 3]) -> [Card; 3] {
+    let mut sorted = cards.clone();
+    sorted.sort_by_key(set_solver::card_index);
+    sorted
 }
 
-fn is_set_out(board: &Vec<Card>) -> bool {
-    for i in 0..(board.len() - 2) {
-        for j in (i + 1)..(board.len() - 1) {
-            for k in (j + 1)..board.len() {
-                let cards = [board[i].clone(), board[j].clone(), board[k].clone()];
-                if check_set(&cards) {
-                    return true;
+impl Set {
+    pub fn new(name: String, creator: User, settings: SetSettings) -> Self {
+        let creator_player = super::player::Player::from_user(&creator);
+        let (seed, deck, board, puzzle) = match settings.mode {
+            SetMode::Classic | SetMode::Practice => {
+                let seed = settings.seed.unwrap_or_else(|| rand::rng().random());
+                let (mut deck, mut board) = deal_cards(&mut StdRng::seed_from_u64(seed));
+                while !settings.manual_deal && !set_solver::has_set(&board) {
+                    let n = deck.len().min(3);
+                    board.extend(deck.drain(0..n));
                 }
+                (seed, deck, board, None)
             }
+            SetMode::DailyPuzzle => {
+                let date = daily::today();
+                let seed = daily::seed_for(date);
+                let puzzle = DailyPuzzle {
+                    date,
+                    started: HashMap::from([(creator_player.id, Utc::now())]),
+                    found: HashMap::new(),
+                };
+                (seed, Vec::new(), daily::puzzle_board(seed), Some(puzzle))
+            }
+        };
+        let practice = (settings.mode == SetMode::Practice).then(|| {
+            let format = match settings.sprint_sets {
+                Some(sets) => PracticeFormat::Sprint { sets },
+                None => PracticeFormat::FullDeck,
+            };
+            PracticeSession::new(format, &creator.name)
+        });
+        let (mut teams, coop) = match &settings.scoring {
+            Scoring::Individual => (Vec::new(), None),
+            Scoring::Teams { teams } => (teams::build_teams(teams), None),
+            Scoring::Coop { time_limit_secs } => {
+                (Vec::new(), Some(CoopProgress::new(*time_limit_secs)))
+            }
+        };
+        if !teams.is_empty() {
+            teams::assign(&mut teams, creator_player.id, &creator_player.name);
         }
-    }
-    false
-}
-
-impl Set {
-    pub fn new(name: String, creator: User) -> Self {
-        let (mut deck, mut board) = deal_cards();
-        while !is_set_out(&board) {
-            board.extend(deck.drain(0..3));
-        }
-        Self {
+        let round = match (&settings.mode, settings.round_secs) {
+            (SetMode::Classic, Some(secs)) => Some(TimedRound::new(secs, settings.tie_break)),
+            _ => None,
+        };
+        let mut set = Self {
             game_state: super::GameState {
                 id: Uuid::new_v4(),
                 name,
                 broadcast_tx: Arc::new(tokio::sync::broadcast::channel(64).0),
-                players: vec![super::player::Player::from_user(&creator)],
+                players: vec![creator_player],
                 current_state: String::from("in_progress"),
                 chat: Vec::new(),
+                listed: practice.is_none(),
             },
             deck,
             board,
             previous_set: None,
+            settings,
+            seed,
+            puzzle,
+            practice,
+            teams,
+            coop,
+            round,
+            winners: Vec::new(),
+            events: Vec::new(),
+            hints: HashMap::new(),
+            bots: Vec::new(),
+            last_scored: HashMap::new(),
+            player_stats: HashMap::new(),
+            board_dealt_at: Utc::now(),
+        };
+        if set.settings.mode == SetMode::Classic {
+            for difficulty in set.settings.bots.clone() {
+                set.add_bot(difficulty);
+            }
         }
+        set.record_deal();
+        set
+    }
+
+    /// Seat a bot as a new player and return its id.
+    fn add_bot(&mut self, difficulty: BotDifficulty) -> Uuid {
+        let base = format!("{} Bot", difficulty.label());
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.game_state.players.iter().any(|p| p.name == name) {
+            n += 1;
+            name = format!("{} {}", base, n);
+        }
+        let player = super::player::Player::bot(name);
+        let id = player.id;
+        self.join_team(&player);
+        self.game_state.players.push(player);
+        self.bots.push(SetBot::new(id, difficulty));
+        id
+    }
+
+    fn join_team(&mut self, player: &super::player::Player) {
+        if !self.teams.is_empty() {
+            teams::assign(&mut self.teams, player.id, &player.name);
+        }
+    }
+
+    /// Log the current board and return its stats. Bots start looking at the new board.
+    fn record_deal(&mut self) -> BoardStats {
+        for bot in self.bots.iter_mut() {
+            bot.board_changed();
+        }
+        self.board_dealt_at = Utc::now();
+        let stats = set_solver::board_stats(&self.board);
+        self.events.push(SetEvent::Deal {
+            at: Utc::now(),
+            board: self.board.clone(),
+            stats: stats.clone(),
+        });
+        stats
     }
 
     fn remove_cards(&mut self, indicies: [u8; 3]) {
@@ -121,6 +407,38 @@ impl Set {
         }
     }
 
+    /// Take a found set off the board, refilling from whatever is left of the deck, and end the
+    /// game once the deck is empty and no set remains.
+    fn replace_set(&mut self, indices: [u8; 3]) {
+        if self.board.len() > 12 || self.deck.is_empty() {
+            self.remove_cards(indices);
+        } else {
+            // fill as many gaps as the deck allows and close up the rest
+            let mut sorted = indices;
+            sorted.sort_unstable();
+            let mut emptied = Vec::new();
+            for &i in &sorted {
+                match self.deck.pop() {
+                    Some(card) => self.board[i as usize] = card,
+                    None => emptied.push(i),
+                }
+            }
+            for &i in emptied.iter().rev() {
+                self.board.remove(i as usize);
+            }
+        }
+        while !self.settings.manual_deal
+            && !self.deck.is_empty()
+            && !set_solver::has_set(&self.board)
+        {
+            let n = self.deck.len().min(3);
+            self.board.extend(self.deck.drain(0..n));
+        }
+        if self.deck.is_empty() && !set_solver::has_set(&self.board) {
+            self.game_state.current_state = "game_over".into();
+        }
+    }
+
     fn set_found(
         &mut self,
         set_card_indicies: [u8; 3],
@@ -131,9 +449,20 @@ impl Set {
         self.previous_set = Some(set_cards.to_vec());
 
         // Get the player's name who found the set
+        let find_ms = (Utc::now() - self.board_dealt_at).num_milliseconds();
         let player_name = if let Some(pid) = player_id {
-            if let Some(player) = self.game_state.players.iter_mut().find(|p| p.id == pid) {
-                player.score += 1;
+            if let Some(player) = self.game_state.players.iter().find(|p| p.id == pid) {
+                let stats = self.player_stats.entry(pid).or_default();
+                stats.sets_found += 1;
+                stats.fastest_find_ms =
+                    Some(stats.fastest_find_ms.map_or(find_ms, |f| f.min(find_ms)));
+                self.last_scored.insert(pid, Utc::now());
+                self.events.push(SetEvent::Find {
+                    at: Utc::now(),
+                    player_id: pid,
+                    cards: set_cards.clone(),
+                    reaction_ms: find_ms,
+                });
                 Some(player.name.clone())
             } else {
                 None
@@ -141,22 +470,24 @@ impl Set {
         } else {
             None
         };
+        if let (Some(pid), Some(_)) = (player_id, &player_name) {
+            self.adjust_score(pid, 1);
+        }
+        if let Some(coop) = self.coop.as_mut() {
+            coop.combined_score += 1;
+        }
 
-        if !self.deck.is_empty() {
-            if self.board.len() > 12 {
-                self.remove_cards(set_card_indicies);
-            } else {
-                for &i in &set_card_indicies {
-                    self.board[i as usize] = self.deck.pop().unwrap();
-                }
-            }
-            while !is_set_out(&self.board) {
-                self.board.extend(self.deck.drain(0..3));
-            }
-        } else {
-            self.remove_cards(set_card_indicies);
-            if !is_set_out(&self.board) {
-                self.game_state.current_state = "game_over".into();
+        self.replace_set(set_card_indicies);
+        let board_stats = self.record_deal();
+
+        // in sudden death, the first find that breaks the tie wins the round
+        let standings = self.standings();
+        let mut round_decided = false;
+        if let Some(round) = self.round.as_mut().filter(|r| r.sudden_death) {
+            let winners = round.decide(&standings);
+            if !winners.is_empty() {
+                self.winners = winners;
+                round_decided = true;
             }
         }
 
@@ -173,6 +504,9 @@ impl Set {
             cards: Some(self.previous_set.clone().unwrap_or_default()),
             message_type: Some("success".to_string()),
         });
+        if let Some(name) = &player_name {
+            self.update_practice(name);
+        }
 
         let set_found_data = SetFoundData {
             game_state: self.game_state.clone(),
@@ -181,6 +515,10 @@ impl Set {
             previous_set: self.previous_set.clone(),
             finder_name: player_name,
             chat: self.game_state.chat.clone(),
+            board_stats,
+            practice: self.practice.clone(),
+            teams: self.teams.clone(),
+            coop: self.coop.clone(),
         };
 
         let msg = Message {
@@ -189,16 +527,499 @@ impl Set {
         };
         let json = serde_json::to_string(&msg).unwrap();
         let _ = self.game_state.broadcast_tx.send(json);
+
+        if round_decided {
+            self.end_game(false);
+        } else if self.game_state.current_state == "game_over" {
+            // running out of cards counts as clearing the deck
+            self.end_game(true);
+        }
+    }
+
+    /// Add `delta` to a player's score and to their team's, stopping at zero.
+    fn adjust_score(&mut self, player_id: Uuid, delta: i32) {
+        if let Some(player) = self
+            .game_state
+            .players
+            .iter_mut()
+            .find(|p| p.id == player_id)
+        {
+            player.score = player.score.saturating_add_signed(delta);
+        }
+        if let Some(team) = teams::team_of(&self.teams, player_id) {
+            self.teams[team].score = self.teams[team].score.saturating_add_signed(delta);
+        }
+    }
+
+    /// A player claims the board holds no set. A correct call earns a point and three more
+    /// cards (or ends the game when the deck is empty); a wrong call costs a point.
+    fn call_no_set(&mut self, player_id: Uuid) -> Result<(), &'static str> {
+        if !self.settings.manual_deal {
+            return Err("Cards are dealt automatically in this game.");
+        }
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.");
+        }
+        let sets_available = set_solver::count_sets(&self.board);
+        let correct = sets_available == 0;
+        let Some(player) = self.game_state.players.iter().find(|p| p.id == player_id) else {
+            return Err("Only players in this game can call no set.");
+        };
+        let caller_name = player.name.clone();
+        self.adjust_score(player_id, if correct { 1 } else { -1 });
+        let text = if correct {
+            format!("{} called no set and was right!", caller_name)
+        } else {
+            format!(
+                "{} called no set, but there {} {} on the board.",
+                caller_name,
+                if sets_available == 1 { "is" } else { "are" },
+                if sets_available == 1 {
+                    "a set".to_string()
+                } else {
+                    format!("{} sets", sets_available)
+                }
+            )
+        };
+        self.game_state.chat.push(super::ChatMessage {
+            sender: "System".to_string(),
+            text,
+            cards: None,
+            message_type: Some(if correct { "success" } else { "error" }.to_string()),
+        });
+        self.events.push(SetEvent::NoSetCall {
+            at: Utc::now(),
+            player_id,
+            correct,
+        });
+
+        if correct {
+            if self.deck.is_empty() {
+                self.game_state.current_state = "game_over".into();
+            } else {
+                let n = self.deck.len().min(3);
+                self.board.extend(self.deck.drain(0..n));
+                self.record_deal();
+            }
+        }
+
+        let result = NoSetResultData {
+            game_state: self.game_state.clone(),
+            board: self.board.clone(),
+            chat: self.game_state.chat.clone(),
+            caller_name,
+            correct,
+            sets_available,
+        };
+        let msg = Message {
+            kind: "no_set_result".into(),
+            data: serde_json::to_string(&result).unwrap(),
+        };
+        let _ = self
+            .game_state
+            .broadcast_tx
+            .send(serde_json::to_string(&msg).unwrap());
+
+        if self.game_state.current_state == "game_over" {
+            self.end_game(true);
+        }
+        Ok(())
+    }
+
+    /// Final order: score, then sets found, then fewest wrong attempts.
+    fn rankings(&self) -> Vec<Ranking> {
+        let mut rankings: Vec<Ranking> = self
+            .game_state
+            .players
+            .iter()
+            .map(|p| Ranking {
+                rank: 0,
+                player_id: p.id,
+                name: p.name.clone(),
+                score: p.score,
+                is_bot: p.is_bot,
+                stats: self.player_stats.get(&p.id).cloned().unwrap_or_default(),
+            })
+            .collect();
+        rankings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.stats.sets_found.cmp(&a.stats.sets_found))
+                .then(a.stats.wrong_attempts.cmp(&b.stats.wrong_attempts))
+        });
+        for i in 0..rankings.len() {
+            rankings[i].rank = if i > 0 && rankings[i].score == rankings[i - 1].score {
+                rankings[i - 1].rank
+            } else {
+                i + 1
+            };
+        }
+        rankings
+    }
+
+    fn standings(&self) -> Vec<Standing> {
+        self.game_state
+            .players
+            .iter()
+            .map(|p| Standing {
+                id: p.id,
+                score: p.score,
+                last_scored: self.last_scored.get(&p.id).copied(),
+            })
+            .collect()
+    }
+
+    /// Called from `tick` while a timed round is running.
+    fn tick_round(&mut self) {
+        let standings = self.standings();
+        let Some(round) = self.round.as_mut() else {
+            return;
+        };
+        if let Some(secs) = round.next_announcement() {
+            let clock = ClockData {
+                remaining_ms: secs * 1000,
+                sudden_death: round.sudden_death,
+            };
+            let msg = Message {
+                kind: "clock".into(),
+                data: serde_json::to_string(&clock).unwrap(),
+            };
+            let _ = self
+                .game_state
+                .broadcast_tx
+                .send(serde_json::to_string(&msg).unwrap());
+        }
+        if !round.is_expired() || round.sudden_death {
+            return;
+        }
+
+        let winners = round.decide(&standings);
+        if winners.is_empty() {
+            self.game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: "Time's up with a tie at the top: sudden death! The next set found by a leader wins."
+                    .to_string(),
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+            self.send_state_to_client(&self.game_state.broadcast_tx.clone(), "sudden_death".into());
+        } else {
+            self.winners = winners;
+            self.end_game(false);
+        }
+    }
+
+    /// Finish the game and broadcast the final standings. `cleared` says whether the deck was
+    /// worked through (as opposed to the game being cut short, e.g. by a co-op time limit).
+    fn end_game(&mut self, cleared: bool) {
+        self.game_state.current_state = "game_over".into();
+        self.events.push(SetEvent::GameOver { at: Utc::now() });
+        if let Some(coop) = self.coop.as_mut() {
+            coop.finish(cleared);
+            let text = if cleared {
+                format!(
+                    "Deck cleared together with {} sets in {:.1}s!",
+                    coop.combined_score,
+                    coop.elapsed_ms.unwrap_or_default() as f64 / 1000.0
+                )
+            } else {
+                format!("Time's up! The room found {} sets.", coop.combined_score)
+            };
+            self.game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text,
+                cards: None,
+                message_type: Some("info".to_string()),
+            });
+        }
+
+        if self.winners.is_empty() {
+            let top = self.game_state.players.iter().map(|p| p.score).max();
+            self.winners = self
+                .game_state
+                .players
+                .iter()
+                .filter(|p| Some(p.score) == top)
+                .map(|p| p.id)
+                .collect();
+        }
+        let winners = self
+            .game_state
+            .players
+            .iter()
+            .filter(|p| self.winners.contains(&p.id))
+            .map(|p| p.name.clone())
+            .collect();
+
+        let top = self.teams.iter().map(|t| t.score).max();
+        let mut leaders = self.teams.iter().filter(|t| Some(t.score) == top);
+        let winning_team = match (leaders.next(), leaders.next()) {
+            (Some(team), None) => Some(team.name.clone()),
+            _ => None,
+        };
+
+        let game_over_data = GameOverData {
+            game_state: self.game_state.clone(),
+            chat: self.game_state.chat.clone(),
+            rankings: self.rankings(),
+            cards_left: self.board.clone(),
+            teams: self.teams.clone(),
+            winning_team,
+            coop: self.coop.clone(),
+            winners,
+            tie_break: self.round.as_ref().map(|r| r.tie_break),
+        };
+        let msg = Message {
+            kind: "game_over".into(),
+            data: serde_json::to_string(&game_over_data).unwrap(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let _ = self.game_state.broadcast_tx.send(json);
     }
 
     pub fn set_attempted(&mut self, found_set: [u8; 3], player_id: Option<Uuid>) {
-        // Logic to handle a found set
+        if self.game_state.current_state == "game_over" {
+            return;
+        }
+        // ignore stale or malformed selections instead of indexing past the board
+        let [a, b, c] = found_set;
+        if a == b || b == c || a == c || found_set.iter().any(|&i| i as usize >= self.board.len()) {
+            return;
+        }
         let set_cards = &found_set.map(|i| self.board[i as usize].clone());
-        if check_set(set_cards) {
-            self.set_found(found_set, set_cards, player_id);
+        if set_solver::is_set(set_cards) {
+            if self.puzzle.is_some() {
+                if let Some(pid) = player_id {
+                    self.puzzle_set_found(set_cards, pid);
+                }
+            } else {
+                self.set_found(found_set, set_cards, player_id);
+            }
         } else {
+            if let Some(pid) = player_id {
+                self.player_stats.entry(pid).or_default().wrong_attempts += 1;
+                self.events.push(SetEvent::WrongAttempt {
+                    at: Utc::now(),
+                    player_id: pid,
+                    cards: set_cards.clone(),
+                });
+            }
+            if let Some(session) = self.practice.as_mut() {
+                session.record_wrong_attempt();
+                self.send_state_to_client(
+                    &self.game_state.broadcast_tx.clone(),
+                    "practice_update".into(),
+                );
+            }
+        }
+    }
+
+    /// Count a practice find and stop the clock once the run is over.
+    fn update_practice(&mut self, player_name: &str) {
+        let Some(session) = self.practice.as_mut() else {
+            return;
+        };
+        if session.is_finished() {
+            return;
+        }
+        let sprint_done = session.record_find();
+        if !sprint_done && self.game_state.current_state != "game_over" {
+            return;
+        }
+        session.finish(player_name);
+        self.game_state.current_state = "game_over".into();
+
+        let total = session.total_ms.unwrap_or_default() as f64 / 1000.0;
+        let mut text = format!(
+            "Practice complete: {} sets in {:.1}s, {} wrong attempts.",
+            session.reaction_ms.len(),
+            total,
+            session.wrong_attempts
+        );
+        if let Some(avg) = session.average_reaction_ms() {
+            text.push_str(&format!(" Average find: {:.1}s.", avg as f64 / 1000.0));
+        }
+        if session.new_personal_best {
+            text.push_str(" New personal best!");
+        }
+        self.game_state.chat.push(super::ChatMessage {
+            sender: "System".to_string(),
+            text,
+            cards: None,
+            message_type: Some("success".to_string()),
+        });
+    }
+
+    /// Daily puzzle finds leave the board untouched and are only revealed to the finder,
+    /// so other players in the room aren't handed the answer.
+    fn puzzle_set_found(&mut self, set_cards: &[Card; 3], player_id: Uuid) {
+        let Some(puzzle) = self.puzzle.as_mut() else {
+            return;
+        };
+        let Some(player) = self
+            .game_state
+            .players
+            .iter_mut()
+            .find(|p| p.id == player_id)
+        else {
+            return;
+        };
+        let found = puzzle.found.entry(player_id).or_default();
+        let set = normalized_set(set_cards);
+        if found.contains(&set) || found.len() >= daily::PUZZLE_SETS {
             return;
         }
+        found.push(set);
+        player.score = found.len() as u32;
+        // puzzle reaction times run from the player's previous find (or their start)
+        let since = self
+            .events
+            .iter()
+            .rev()
+            .find_map(|e| match e {
+                SetEvent::Find {
+                    at, player_id: p, ..
+                } if *p == player_id => Some(*at),
+                _ => None,
+            })
+            .or_else(|| puzzle.started.get(&player_id).copied())
+            .unwrap_or(self.board_dealt_at);
+        self.events.push(SetEvent::Find {
+            at: Utc::now(),
+            player_id,
+            cards: set_cards.clone(),
+            reaction_ms: (Utc::now() - since).num_milliseconds(),
+        });
+
+        let mut time_ms = None;
+        if found.len() == daily::PUZZLE_SETS && !player.is_bot {
+            let started = puzzle
+                .started
+                .get(&player_id)
+                .copied()
+                .unwrap_or_else(Utc::now);
+            let elapsed = (Utc::now() - started).num_milliseconds();
+            time_ms = Some(elapsed);
+            daily::record_completion(
+                puzzle.date,
+                DailyEntry {
+                    player_name: player.name.clone(),
+                    time_ms: elapsed,
+                    completed_at: Utc::now(),
+                },
+            );
+            self.game_state.chat.push(super::ChatMessage {
+                sender: "System".to_string(),
+                text: format!(
+                    "{} found all {} sets in {:.1}s!",
+                    player.name,
+                    daily::PUZZLE_SETS,
+                    elapsed as f64 / 1000.0
+                ),
+                cards: None,
+                message_type: Some("success".to_string()),
+            });
+        }
+
+        let progress = PuzzleProgressData {
+            found: found.clone(),
+            sets_total: daily::PUZZLE_SETS,
+            time_ms,
+        };
+        self.send_to_player(
+            player_id,
+            "puzzle_progress",
+            serde_json::to_string(&progress).unwrap(),
+        );
+        self.send_state_to_client(&self.game_state.broadcast_tx.clone(), "score_update".into());
+    }
+
+    /// Positions of `cards` on the current board, if all of them are still there.
+    fn board_positions(&self, cards: &[Card; 3]) -> Option<[usize; 3]> {
+        let mut positions = [0; 3];
+        for (slot, card) in cards.iter().enumerate() {
+            positions[slot] = self.board.iter().position(|c| c == card)?;
+        }
+        Some(positions)
+    }
+
+    /// Reveal one more card of a valid set to `player_id`, charging according to the room's
+    /// hint policy. The first hint on a set shows one card, the next shows two.
+    fn request_hint(&mut self, player_id: Uuid) -> Result<HintData, &'static str> {
+        if self.game_state.current_state == "game_over" {
+            return Err("The game is over.");
+        }
+        let score = match self.game_state.players.iter().find(|p| p.id == player_id) {
+            Some(player) => player.score,
+            None => return Err("Only players in this game can ask for hints."),
+        };
+
+        let progress = self.hints.entry(player_id).or_default();
+        let hints_remaining = match self.settings.hints {
+            HintPolicy::Disabled => return Err("Hints are disabled in this game."),
+            HintPolicy::Free => None,
+            HintPolicy::CostsPoint => {
+                if score == 0 {
+                    return Err("You need at least one point to buy a hint.");
+                }
+                None
+            }
+            HintPolicy::Limited { max_hints } => {
+                if progress.used >= max_hints {
+                    return Err("You have no hints left.");
+                }
+                Some(max_hints - progress.used - 1)
+            }
+        };
+
+        // keep revealing the same set while it's still on the board
+        let still_on_board = progress
+            .target
+            .as_ref()
+            .is_some_and(|t| t.iter().all(|c| self.board.contains(c)));
+        if !still_on_board {
+            // in a daily puzzle, point at a set the player hasn't found yet
+            let found = self.puzzle.as_ref().and_then(|p| p.found.get(&player_id));
+            let Some(first) = set_solver::find_sets(&self.board)
+                .into_iter()
+                .find(|s| found.is_none_or(|f| !f.contains(&normalized_set(&s.cards))))
+            else {
+                return Err("There is no set on the board.");
+            };
+            progress.target = Some(first.cards);
+            progress.revealed = 0;
+        }
+        if progress.revealed >= 2 {
+            return Err("You've already been shown two cards of this set.");
+        }
+
+        progress.revealed += 1;
+        progress.used += 1;
+        let target = progress.target.clone().unwrap();
+        let revealed = progress.revealed;
+        let hints_used = progress.used;
+
+        if self.settings.hints == HintPolicy::CostsPoint {
+            self.adjust_score(player_id, -1);
+        }
+
+        let positions = self.board_positions(&target).unwrap();
+        Ok(HintData {
+            indices: positions[..revealed].to_vec(),
+            cards: target[..revealed].to_vec(),
+            hints_used,
+            hints_remaining,
+        })
+    }
+
+    fn send_to_player(&self, player_id: Uuid, kind: &str, data: String) {
+        let msg = DirectMessage {
+            kind: kind.into(),
+            data,
+            recipient: player_id,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let _ = self.game_state.broadcast_tx.send(json);
     }
 }
 
@@ -210,6 +1031,16 @@ impl super::Game for Set {
             board: self.board.clone(),
             previous_set: self.previous_set.clone(),
             chat: self.game_state.chat.clone(),
+            settings: self.settings.clone(),
+            board_stats: set_solver::board_stats(&self.board),
+            seed: self.seed,
+            puzzle: self.puzzle.clone(),
+            daily_leaderboard: self.puzzle.as_ref().map(|p| daily::leaderboard(p.date)),
+            practice: self.practice.clone(),
+            teams: self.teams.clone(),
+            coop: self.coop.clone(),
+            round: self.round.clone(),
+            remaining_ms: self.round.as_ref().map(|r| r.remaining_ms()),
         };
 
         let msg = Message {
@@ -228,6 +1059,46 @@ impl super::Game for Set {
         &self.game_state
     }
 
+    fn analysis(&self) -> Option<serde_json::Value> {
+        if self.game_state.current_state != "game_over" {
+            return None;
+        }
+        let analysis = set_analysis::analyze(&self.events, &self.game_state.players);
+        serde_json::to_value(analysis).ok()
+    }
+
+    fn tick(&mut self) {
+        if self.game_state.current_state == "game_over" {
+            return;
+        }
+        if self.coop.as_ref().is_some_and(|c| c.is_out_of_time()) {
+            self.end_game(false);
+            return;
+        }
+        self.tick_round();
+        if self.game_state.current_state == "game_over" {
+            return;
+        }
+        // one claim per tick; the claim redeals and resets every bot's clock
+        let claim = self
+            .bots
+            .iter()
+            .find_map(|bot| Some((bot.next_move(&self.board)?, bot.player_id)));
+        if let Some((indices, bot_id)) = claim {
+            self.set_attempted(indices.map(|i| i as u8), Some(bot_id));
+        } else if self.settings.manual_deal && !set_solver::has_set(&self.board) {
+            // a bot that has stared at a set-less board long enough calls it
+            let caller = self
+                .bots
+                .iter()
+                .find(|bot| bot.is_ready())
+                .map(|b| b.player_id);
+            if let Some(bot_id) = caller {
+                let _ = self.call_no_set(bot_id);
+            }
+        }
+    }
+
     fn handle_game_socket_message(&mut self, txt: String) {
         let json_in = serde_json::from_str::<serde_json::Value>(&txt);
         if let Ok(parsed) = json_in {
@@ -293,6 +1164,79 @@ impl super::Game for Set {
                         );
                     }
                 }
+                "request_hint" => {
+                    let player_id = parsed
+                        .get("player_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| Uuid::parse_str(s).ok());
+
+                    if let Some(player_id) = player_id {
+                        match self.request_hint(player_id) {
+                            Ok(hint) => {
+                                self.send_to_player(
+                                    player_id,
+                                    "hint",
+                                    serde_json::to_string(&hint).unwrap(),
+                                );
+                                if self.settings.hints == HintPolicy::CostsPoint {
+                                    // everyone sees the score change, not the hint itself
+                                    self.send_state_to_client(
+                                        &self.game_state.broadcast_tx.clone(),
+                                        "hint_used".into(),
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                let chat_msg = super::ChatMessage {
+                                    sender: "System".to_string(),
+                                    text: e.to_string(),
+                                    cards: None,
+                                    message_type: Some("error".to_string()),
+                                };
+                                self.send_to_player(
+                                    player_id,
+                                    "chat",
+                                    serde_json::to_string(&chat_msg).unwrap(),
+                                );
+                            }
+                        }
+                    }
+                }
+                "call_no_set" => {
+                    let player_id = parsed
+                        .get("player_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| Uuid::parse_str(s).ok());
+
+                    if let Some(player_id) = player_id {
+                        if let Err(e) = self.call_no_set(player_id) {
+                            let chat_msg = super::ChatMessage {
+                                sender: "System".to_string(),
+                                text: e.to_string(),
+                                cards: None,
+                                message_type: Some("error".to_string()),
+                            };
+                            self.send_to_player(
+                                player_id,
+                                "chat",
+                                serde_json::to_string(&chat_msg).unwrap(),
+                            );
+                        }
+                    }
+                }
+                "add_bot" => {
+                    let difficulty = parsed
+                        .get("data")
+                        .and_then(|v| serde_json::from_value::<BotDifficulty>(v.clone()).ok())
+                        .unwrap_or_default();
+                    if self.settings.mode == SetMode::Classic {
+                        self.add_bot(difficulty);
+                        self.send_state_to_client(
+                            &self.game_state.broadcast_tx.clone(),
+                            "player_joined".into(),
+                        );
+                    }
+                }
                 "join_player" => {
                     let data = parsed
                         .get("data")
@@ -314,10 +1258,16 @@ impl super::Game for Set {
                             .iter()
                             .any(|p| p.name == player_name);
 
-                        if !player_exists {
+                        // practice rooms belong to their creator; others may only watch
+                        if !player_exists && self.practice.is_none() {
                             // Add new player
                             let new_player =
                                 super::player::Player::new(player_name.clone(), Uuid::new_v4());
+                            // the puzzle clock starts when each player arrives
+                            if let Some(puzzle) = self.puzzle.as_mut() {
+                                puzzle.started.insert(new_player.id, Utc::now());
+                            }
+                            self.join_team(&new_player);
                             self.game_state.players.push(new_player);
 
                             // Broadcast updated game state to all clients
@@ -341,3 +1291,288 @@ impl super::Game for Set {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::User;
+
+    fn card(a: u8, b: u8, c: u8, d: u8) -> Card {
+        Card {
+            array: [a, b, c, d],
+        }
+    }
+
+    fn game_with(settings: SetSettings) -> (Set, Uuid) {
+        let creator = User::new("Alice".to_string());
+        let mut game = Set::new("test".to_string(), creator, settings);
+        // a board holding exactly one set: the first three cards
+        game.board = vec![
+            card(0, 0, 0, 0),
+            card(1, 1, 1, 1),
+            card(2, 2, 2, 2),
+            card(0, 0, 0, 1),
+        ];
+        let player_id = game.game_state.players[0].id;
+        (game, player_id)
+    }
+
+    #[test]
+    fn test_hint_reveals_progressively() {
+        let (mut game, player_id) = game_with(SetSettings::default());
+        let first = game.request_hint(player_id).unwrap();
+        assert_eq!(first.indices, vec![0]);
+        let second = game.request_hint(player_id).unwrap();
+        assert_eq!(second.indices, vec![0, 1]);
+        assert_eq!(second.hints_used, 2);
+        assert!(game.request_hint(player_id).is_err());
+    }
+
+    #[test]
+    fn test_hint_policies() {
+        let (mut game, player_id) = game_with(SetSettings {
+            hints: HintPolicy::Disabled,
+            ..Default::default()
+        });
+        assert!(game.request_hint(player_id).is_err());
+
+        let (mut game, player_id) = game_with(SetSettings {
+            hints: HintPolicy::CostsPoint,
+            ..Default::default()
+        });
+        assert!(game.request_hint(player_id).is_err());
+        game.game_state.players[0].score = 1;
+        assert!(game.request_hint(player_id).is_ok());
+        assert_eq!(game.game_state.players[0].score, 0);
+
+        // in team games the hint comes off the team total too
+        let (mut game, player_id) = game_with(SetSettings {
+            hints: HintPolicy::CostsPoint,
+            scoring: Scoring::Teams { teams: Vec::new() },
+            ..Default::default()
+        });
+        game.set_attempted([0, 1, 2], Some(player_id));
+        assert!(game.request_hint(player_id).is_ok());
+        assert_eq!(game.game_state.players[0].score, 0);
+        assert_eq!(game.teams[0].score, 0);
+
+        game.game_state.current_state = "game_over".into();
+        game.game_state.players[0].score = 1;
+        assert!(game.request_hint(player_id).is_err());
+        assert_eq!(game.game_state.players[0].score, 1);
+
+        let (mut game, player_id) = game_with(SetSettings {
+            hints: HintPolicy::Limited { max_hints: 1 },
+            ..Default::default()
+        });
+        assert_eq!(
+            game.request_hint(player_id).unwrap().hints_remaining,
+            Some(0)
+        );
+        assert!(game.request_hint(player_id).is_err());
+    }
+
+    #[test]
+    fn test_seeded_games_deal_the_same_board() {
+        let settings = SetSettings {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let a = Set::new(
+            "a".to_string(),
+            User::new("A".to_string()),
+            settings.clone(),
+        );
+        let b = Set::new("b".to_string(), User::new("B".to_string()), settings);
+        assert_eq!(a.board, b.board);
+        assert_eq!(a.deck, b.deck);
+    }
+
+    #[test]
+    fn test_daily_puzzle_completion() {
+        let settings = SetSettings {
+            mode: SetMode::DailyPuzzle,
+            ..Default::default()
+        };
+        let mut game = Set::new("daily".to_string(), User::new("Dana".to_string()), settings);
+        let player_id = game.game_state.players[0].id;
+        let sets = set_solver::find_sets(&game.board);
+        assert_eq!(sets.len(), daily::PUZZLE_SETS);
+
+        for set in &sets {
+            let indices = set.indices.map(|i| i as u8);
+            game.set_attempted(indices, Some(player_id));
+            // finding the same set twice doesn't count
+            game.set_attempted(indices, Some(player_id));
+        }
+        assert_eq!(game.board.len(), daily::PUZZLE_CARDS);
+        assert_eq!(game.game_state.players[0].score, daily::PUZZLE_SETS as u32);
+        let date = game.puzzle.as_ref().unwrap().date;
+        assert!(daily::leaderboard(date)
+            .iter()
+            .any(|e| e.player_name == "Dana"));
+    }
+
+    #[test]
+    fn test_practice_sprint() {
+        let settings = SetSettings {
+            mode: SetMode::Practice,
+            sprint_sets: Some(2),
+            ..Default::default()
+        };
+        let mut game = Set::new("solo".to_string(), User::new("Pat".to_string()), settings);
+        assert!(!game.game_state.listed);
+        let player_id = game.game_state.players[0].id;
+
+        game.set_attempted([0, 1, 2], Some(player_id));
+        for _ in 0..2 {
+            let indices = set_solver::find_sets(&game.board)[0]
+                .indices
+                .map(|i| i as u8);
+            game.set_attempted(indices, Some(player_id));
+        }
+        let session = game.practice.as_ref().unwrap();
+        assert_eq!(session.reaction_ms.len(), 2);
+        assert!(session.is_finished());
+        assert_eq!(game.game_state.current_state, "game_over");
+    }
+
+    #[test]
+    fn test_bots_are_seated_and_claim_sets() {
+        let settings = SetSettings {
+            bots: vec![BotDifficulty::Perfect],
+            ..Default::default()
+        };
+        let mut game = Set::new("bots".to_string(), User::new("Human".to_string()), settings);
+        assert_eq!(game.game_state.players.len(), 2);
+        let bot = game.game_state.players[1].clone();
+        assert!(bot.is_bot);
+        assert_eq!(bot.name, "Perfect Bot");
+
+        // nothing happens before the bot's delay has passed
+        game.tick();
+        assert_eq!(game.game_state.players[1].score, 0);
+        std::thread::sleep(std::time::Duration::from_millis(1600));
+        game.tick();
+        assert_eq!(game.game_state.players[1].score, 1);
+    }
+
+    #[test]
+    fn test_team_scores_and_game_over() {
+        let settings = SetSettings {
+            scoring: Scoring::Teams { teams: Vec::new() },
+            bots: vec![BotDifficulty::Easy],
+            ..Default::default()
+        };
+        let (mut game, player_id) = game_with(settings);
+        game.deck.clear();
+        assert_eq!(game.teams[0].members, vec![player_id]);
+        assert_eq!(game.teams[1].members.len(), 1);
+
+        game.set_attempted([0, 1, 2], Some(player_id));
+        assert_eq!(game.teams[0].score, 1);
+        assert_eq!(game.teams[1].score, 0);
+        // the last set on an empty deck ends the game
+        assert_eq!(game.game_state.current_state, "game_over");
+    }
+
+    #[test]
+    fn test_coop_time_limit() {
+        let settings = SetSettings {
+            scoring: Scoring::Coop {
+                time_limit_secs: Some(0),
+            },
+            ..Default::default()
+        };
+        let (mut game, _) = game_with(settings);
+        game.tick();
+        assert_eq!(game.game_state.current_state, "game_over");
+        assert_eq!(game.coop.as_ref().unwrap().cleared, Some(false));
+    }
+
+    #[test]
+    fn test_timed_round_sudden_death() {
+        let settings = SetSettings {
+            round_secs: Some(0),
+            tie_break: TieBreak::SuddenDeath,
+            bots: vec![BotDifficulty::Easy],
+            ..Default::default()
+        };
+        let (mut game, player_id) = game_with(settings);
+        assert_eq!(game.round.as_ref().unwrap().remaining_ms(), 0);
+
+        // both players are on zero when time runs out
+        game.tick();
+        assert!(game.round.as_ref().unwrap().sudden_death);
+        assert_eq!(game.game_state.current_state, "in_progress");
+
+        game.set_attempted([0, 1, 2], Some(player_id));
+        assert_eq!(game.game_state.current_state, "game_over");
+        assert_eq!(game.winners, vec![player_id]);
+    }
+
+    #[test]
+    fn test_no_set_calls() {
+        let settings = SetSettings {
+            manual_deal: true,
+            ..Default::default()
+        };
+        let (mut game, player_id) = game_with(settings);
+
+        // the board still holds a set, so the call is wrong
+        game.game_state.players[0].score = 2;
+        game.call_no_set(player_id).unwrap();
+        assert_eq!(game.game_state.players[0].score, 1);
+
+        game.board.truncate(2);
+        let deck_before = game.deck.len();
+        game.call_no_set(player_id).unwrap();
+        assert_eq!(game.game_state.players[0].score, 2);
+        assert_eq!(game.board.len(), 5);
+        assert_eq!(game.deck.len(), deck_before - 3);
+
+        let (mut game, player_id) = game_with(SetSettings::default());
+        assert!(game.call_no_set(player_id).is_err());
+    }
+
+    #[test]
+    fn test_end_of_deck_with_short_deck() {
+        let (mut game, player_id) = game_with(SetSettings::default());
+        // one card left in the deck: it fills one gap and the other two close up
+        game.deck = vec![card(2, 1, 0, 0)];
+        game.set_attempted([0, 1, 2], Some(player_id));
+        assert!(game.deck.is_empty());
+        assert_eq!(game.board.len(), 2);
+        assert_eq!(game.game_state.current_state, "game_over");
+
+        let rankings = game.rankings();
+        assert_eq!(rankings[0].rank, 1);
+        assert_eq!(rankings[0].stats.sets_found, 1);
+        assert!(rankings[0].stats.fastest_find_ms.is_some());
+    }
+
+    #[test]
+    fn test_wrong_attempts_are_counted() {
+        let (mut game, player_id) = game_with(SetSettings::default());
+        game.set_attempted([0, 1, 3], Some(player_id));
+        assert_eq!(game.player_stats[&player_id].wrong_attempts, 1);
+    }
+
+    #[test]
+    fn test_analysis_available_after_game_over() {
+        let (mut game, player_id) = game_with(SetSettings::default());
+        game.deck.clear();
+        assert!(game.analysis().is_none());
+        game.set_attempted([0, 1, 3], Some(player_id));
+        game.set_attempted([0, 1, 2], Some(player_id));
+        assert_eq!(game.game_state.current_state, "game_over");
+        assert!(matches!(
+            game.events.last(),
+            Some(SetEvent::GameOver { .. })
+        ));
+
+        let analysis = game.analysis().unwrap();
+        assert_eq!(analysis["players"][0]["sets_found"], 1);
+        assert_eq!(analysis["players"][0]["wrong_attempts"], 1);
+    }
+}
@@ -0,0 +1,178 @@
+// Post-game analysis of a Set game, rebuilt from its event log.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::player::Player;
+use super::set::{Card, SetEvent};
+use super::set_solver::{self, FoundSet};
+
+#[derive(Debug, Serialize)]
+pub struct FoundBy {
+    pub player_name: String,
+    pub cards: [Card; 3],
+    pub reaction_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardAnalysis {
+    pub dealt_at: DateTime<Utc>,
+    pub cards: Vec<Card>,
+    pub sets_available: Vec<FoundSet>,
+    pub found: Vec<FoundBy>,
+    /// How long the board stood before the next deal (or the end of the game)
+    pub stood_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerAnalysis {
+    pub player_id: Uuid,
+    pub name: String,
+    pub sets_found: usize,
+    pub average_reaction_ms: Option<i64>,
+    pub wrong_attempts: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameAnalysis {
+    pub boards: Vec<BoardAnalysis>,
+    pub players: Vec<PlayerAnalysis>,
+}
+
+fn player_name(players: &[Player], id: Uuid) -> String {
+    players
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Walk the event log, attributing each find to the board that was showing at the time.
+pub fn analyze(events: &[SetEvent], players: &[Player]) -> GameAnalysis {
+    let mut boards: Vec<BoardAnalysis> = Vec::new();
+    let mut end = Utc::now();
+
+    for event in events {
+        match event {
+            SetEvent::Deal { at, board, .. } => {
+                if let Some(last) = boards.last_mut() {
+                    last.stood_ms = (*at - last.dealt_at).num_milliseconds();
+                }
+                boards.push(BoardAnalysis {
+                    dealt_at: *at,
+                    cards: board.clone(),
+                    sets_available: set_solver::find_sets(board),
+                    found: Vec::new(),
+                    stood_ms: 0,
+                });
+            }
+            SetEvent::Find {
+                player_id,
+                cards,
+                reaction_ms,
+                ..
+            } => {
+                if let Some(last) = boards.last_mut() {
+                    last.found.push(FoundBy {
+                        player_name: player_name(players, *player_id),
+                        cards: cards.clone(),
+                        reaction_ms: *reaction_ms,
+                    });
+                }
+            }
+            SetEvent::GameOver { at } => end = *at,
+            SetEvent::WrongAttempt { .. } | SetEvent::NoSetCall { .. } => {}
+        }
+    }
+    if let Some(last) = boards.last_mut() {
+        last.stood_ms = (end - last.dealt_at).num_milliseconds();
+    }
+
+    let players = players
+        .iter()
+        .map(|p| {
+            let reactions: Vec<i64> = events
+                .iter()
+                .filter_map(|e| match e {
+                    SetEvent::Find {
+                        player_id,
+                        reaction_ms,
+                        ..
+                    } if *player_id == p.id => Some(*reaction_ms),
+                    _ => None,
+                })
+                .collect();
+            let wrong_attempts = events
+                .iter()
+                .filter(
+                    |e| matches!(e, SetEvent::WrongAttempt { player_id, .. } if *player_id == p.id),
+                )
+                .count();
+            PlayerAnalysis {
+                player_id: p.id,
+                name: p.name.clone(),
+                sets_found: reactions.len(),
+                average_reaction_ms: (!reactions.is_empty())
+                    .then(|| reactions.iter().sum::<i64>() / reactions.len() as i64),
+                wrong_attempts,
+            }
+        })
+        .collect();
+
+    GameAnalysis { boards, players }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn card(a: u8, b: u8, c: u8, d: u8) -> Card {
+        Card {
+            array: [a, b, c, d],
+        }
+    }
+
+    #[test]
+    fn test_finds_are_attributed_to_their_board() {
+        let player = Player::new("Alice".to_string(), Uuid::new_v4());
+        let start = Utc::now();
+        let board = vec![card(0, 0, 0, 0), card(1, 1, 1, 1), card(2, 2, 2, 2)];
+        let set = [board[0].clone(), board[1].clone(), board[2].clone()];
+        let events = vec![
+            SetEvent::Deal {
+                at: start,
+                board: board.clone(),
+                stats: set_solver::board_stats(&board),
+            },
+            SetEvent::WrongAttempt {
+                at: start,
+                player_id: player.id,
+                cards: set.clone(),
+            },
+            SetEvent::Find {
+                at: start + Duration::seconds(4),
+                player_id: player.id,
+                cards: set,
+                reaction_ms: 4000,
+            },
+            SetEvent::Deal {
+                at: start + Duration::seconds(4),
+                board: Vec::new(),
+                stats: set_solver::board_stats(&[]),
+            },
+            SetEvent::GameOver {
+                at: start + Duration::seconds(5),
+            },
+        ];
+
+        let analysis = analyze(&events, &[player]);
+        assert_eq!(analysis.boards.len(), 2);
+        assert_eq!(analysis.boards[0].sets_available.len(), 1);
+        assert_eq!(analysis.boards[0].found[0].player_name, "Alice");
+        assert_eq!(analysis.boards[0].stood_ms, 4000);
+        assert_eq!(analysis.boards[1].stood_ms, 1000);
+        assert_eq!(analysis.players[0].average_reaction_ms, Some(4000));
+        assert_eq!(analysis.players[0].wrong_attempts, 1);
+    }
+}
@@ -0,0 +1,113 @@
+// Computer opponents for Set. A bot is an ordinary `Player` in the room; after each deal it
+// waits a delay drawn for its difficulty and then claims a set picked by the solver.
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::set::Card;
+use super::set_solver;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    /// Claims a set almost as soon as it appears
+    Perfect,
+}
+
+impl BotDifficulty {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Medium => "Medium",
+            BotDifficulty::Hard => "Hard",
+            BotDifficulty::Perfect => "Perfect",
+        }
+    }
+
+    /// Shortest and longest time (in seconds) the bot takes to spot a set.
+    fn delay_range(&self) -> (f64, f64) {
+        match self {
+            BotDifficulty::Easy => (15.0, 45.0),
+            BotDifficulty::Medium => (8.0, 24.0),
+            BotDifficulty::Hard => (4.0, 12.0),
+            BotDifficulty::Perfect => (1.0, 1.5),
+        }
+    }
+
+    /// Sample a reaction delay. Averaging two uniform draws gives a triangular distribution,
+    /// so most delays land near the middle of the range.
+    pub fn sample_delay(&self, rng: &mut impl Rng) -> Duration {
+        let (min, max) = self.delay_range();
+        let t = (rng.random::<f64>() + rng.random::<f64>()) / 2.0;
+        Duration::from_secs_f64(min + (max - min) * t)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetBot {
+    pub player_id: Uuid,
+    pub difficulty: BotDifficulty,
+    next_move_at: Instant,
+}
+
+impl SetBot {
+    pub fn new(player_id: Uuid, difficulty: BotDifficulty) -> Self {
+        let mut bot = SetBot {
+            player_id,
+            difficulty,
+            next_move_at: Instant::now(),
+        };
+        bot.board_changed();
+        bot
+    }
+
+    /// Start thinking about a freshly dealt board.
+    pub fn board_changed(&mut self) {
+        self.next_move_at = Instant::now() + self.difficulty.sample_delay(&mut rand::rng());
+    }
+
+    /// Whether the bot has finished looking at the current board.
+    pub fn is_ready(&self) -> bool {
+        Instant::now() >= self.next_move_at
+    }
+
+    /// The set this bot claims now, if its delay has run out and the board holds one.
+    pub fn next_move(&self, board: &[Card]) -> Option<[usize; 3]> {
+        if !self.is_ready() {
+            return None;
+        }
+        let sets = set_solver::find_sets(board);
+        if sets.is_empty() {
+            return None;
+        }
+        let pick = rand::rng().random_range(0..sets.len());
+        Some(sets[pick].indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_stay_in_range() {
+        let mut rng = rand::rng();
+        for difficulty in [
+            BotDifficulty::Easy,
+            BotDifficulty::Medium,
+            BotDifficulty::Hard,
+            BotDifficulty::Perfect,
+        ] {
+            let (min, max) = difficulty.delay_range();
+            for _ in 0..100 {
+                let delay = difficulty.sample_delay(&mut rng).as_secs_f64();
+                assert!(delay >= min && delay <= max);
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+// Set solver built on the fact that any two cards determine the unique third card
+// completing a set, so a board only needs O(n²) pair checks against a card lookup.
+use serde::Serialize;
+
+use super::set::Card;
+
+/// Number of distinct cards in a deck (3^4).
+const DECK_SIZE: usize = 81;
+
+/// A set found on the board: ascending board indices and the cards at them.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct FoundSet {
+    pub indices: [usize; 3],
+    pub cards: [Card; 3],
+}
+
+/// Summary of one board, as reported with every deal.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct BoardStats {
+    pub cards_on_board: usize,
+    pub sets_available: usize,
+}
+
+/// Position of `card` in a full 81-card deck.
+pub fn card_index(card: &Card) -> usize {
+    card.array
+        .iter()
+        .fold(0, |acc, &attr| acc * 3 + attr as usize)
+}
+
+/// The only card that forms a set with `a` and `b`.
+pub fn third_card(a: &Card, b: &Card) -> Card {
+    let mut array = [0; 4];
+    for (field, attr) in array.iter_mut().enumerate() {
+        *attr = (6 - a.array[field] - b.array[field]) % 3;
+    }
+    Card { array }
+}
+
+pub fn is_set(cards: &[Card; 3]) -> bool {
+    third_card(&cards[0], &cards[1]) == cards[2]
+}
+
+/// Calls `on_set` for each set on the board, in ascending index order, until it returns false.
+fn for_each_set(board: &[Card], mut on_set: impl FnMut([usize; 3]) -> bool) {
+    let mut lookup = [None; DECK_SIZE];
+    for (i, card) in board.iter().enumerate() {
+        lookup[card_index(card)] = Some(i);
+    }
+    for i in 0..board.len() {
+        for j in (i + 1)..board.len() {
+            // only accept k > j so each set is reported once
+            if let Some(k) = lookup[card_index(&third_card(&board[i], &board[j]))] {
+                if k > j && !on_set([i, j, k]) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Every set on the board.
+pub fn find_sets(board: &[Card]) -> Vec<FoundSet> {
+    let mut sets = Vec::new();
+    for_each_set(board, |indices| {
+        sets.push(FoundSet {
+            indices,
+            cards: indices.map(|i| board[i].clone()),
+        });
+        true
+    });
+    sets
+}
+
+pub fn count_sets(board: &[Card]) -> usize {
+    let mut count = 0;
+    for_each_set(board, |_| {
+        count += 1;
+        true
+    });
+    count
+}
+
+pub fn has_set(board: &[Card]) -> bool {
+    let mut found = false;
+    for_each_set(board, |_| {
+        found = true;
+        false
+    });
+    found
+}
+
+pub fn board_stats(board: &[Card]) -> BoardStats {
+    BoardStats {
+        cards_on_board: board.len(),
+        sets_available: count_sets(board),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(a: u8, b: u8, c: u8, d: u8) -> Card {
+        Card {
+            array: [a, b, c, d],
+        }
+    }
+
+    fn brute_force_count(board: &[Card]) -> usize {
+        let mut count = 0;
+        for i in 0..board.len() {
+            for j in (i + 1)..board.len() {
+                for k in (j + 1)..board.len() {
+                    let cards = [board[i].clone(), board[j].clone(), board[k].clone()];
+                    if cards_form_set(&cards) {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn cards_form_set(cards: &[Card; 3]) -> bool {
+        (0..4)
+            .all(|f| (cards[0].array[f] + cards[1].array[f] + cards[2].array[f]).is_multiple_of(3))
+    }
+
+    #[test]
+    fn test_third_card() {
+        assert_eq!(
+            third_card(&card(0, 1, 2, 0), &card(0, 2, 2, 1)),
+            card(0, 0, 2, 2)
+        );
+        assert!(is_set(&[
+            card(0, 0, 0, 0),
+            card(1, 1, 1, 1),
+            card(2, 2, 2, 2)
+        ]));
+        assert!(!is_set(&[
+            card(0, 0, 0, 0),
+            card(1, 1, 1, 1),
+            card(2, 2, 2, 1)
+        ]));
+    }
+
+    #[test]
+    fn test_small_boards() {
+        assert!(find_sets(&[]).is_empty());
+        assert!(!has_set(&[card(0, 0, 0, 0)]));
+        assert!(!has_set(&[card(0, 0, 0, 0), card(1, 1, 1, 1)]));
+    }
+
+    #[test]
+    fn test_find_sets_indices() {
+        let board = vec![
+            card(0, 0, 0, 0),
+            card(0, 0, 0, 1),
+            card(1, 1, 1, 1),
+            card(2, 2, 2, 2),
+        ];
+        let sets = find_sets(&board);
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].indices, [0, 2, 3]);
+        assert_eq!(board_stats(&board).sets_available, 1);
+    }
+
+    #[test]
+    fn test_full_deck_matches_brute_force() {
+        let mut deck = Vec::new();
+        for i in 0..DECK_SIZE as u8 {
+            deck.push(card(i / 27, i / 9 % 3, i / 3 % 3, i % 3));
+        }
+        // every pair of the 81 cards lies in exactly one set
+        assert_eq!(count_sets(&deck), 1080);
+        assert_eq!(count_sets(&deck[..20]), brute_force_count(&deck[..20]));
+    }
+}
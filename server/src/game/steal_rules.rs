@@ -0,0 +1,90 @@
+// House rules for what counts as a legal Anagrams steal. Each room picks any combination;
+// a steal has to pass every rule, and the first one it breaks explains why to the player.
+use serde::{Deserialize, Serialize};
+
+use super::anagrams::are_lemmas_equal;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StealRule {
+    /// The new word can't be a form of the old one ("run" -> "running", "eat" -> "ate")
+    NoSharedRoot,
+    /// Adding just an "s" (or "es") isn't a steal
+    NoPlainS,
+    /// Tacking "ed" or "ing" onto the end isn't a steal
+    NoEdIng,
+    /// The old word can't appear unbroken inside the new one; its letters must be rearranged
+    MustRearrange,
+}
+
+impl StealRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StealRule::NoSharedRoot => "No shared roots",
+            StealRule::NoPlainS => "No plain S",
+            StealRule::NoEdIng => "No -ed/-ing",
+            StealRule::MustRearrange => "Must rearrange",
+        }
+    }
+
+    /// Ok if stealing `existing` to make `new_word` is allowed; otherwise the reason it isn't.
+    /// Both words are expected in lowercase.
+    pub fn check(&self, existing: &str, new_word: &str) -> Result<(), String> {
+        let added = new_word.strip_prefix(existing);
+        let broken = match self {
+            StealRule::NoSharedRoot => are_lemmas_equal(existing, new_word),
+            StealRule::NoPlainS => matches!(added, Some("s" | "es")),
+            StealRule::NoEdIng => matches!(added, Some("d" | "ed" | "ing")),
+            StealRule::MustRearrange => new_word.contains(existing),
+        };
+        if !broken {
+            return Ok(());
+        }
+        Err(match self {
+            StealRule::NoSharedRoot => {
+                format!("{} shares a root with {}.", new_word, existing)
+            }
+            StealRule::NoPlainS => format!("Just adding an S to {} doesn't count.", existing),
+            StealRule::NoEdIng => {
+                format!("Just adding -ed or -ing to {} doesn't count.", existing)
+            }
+            StealRule::MustRearrange => {
+                format!("{} has to be rearranged, not just extended.", existing)
+            }
+        })
+    }
+}
+
+/// Check a steal against every rule, returning the first rejection.
+pub fn check_all(rules: &[StealRule], existing: &str, new_word: &str) -> Result<(), String> {
+    rules
+        .iter()
+        .try_for_each(|rule| rule.check(existing, new_word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_reject_with_reasons() {
+        assert!(StealRule::NoPlainS.check("cat", "cats").is_err());
+        assert!(StealRule::NoPlainS.check("cat", "cast").is_ok());
+        assert!(StealRule::NoEdIng.check("walk", "walking").is_err());
+        assert!(StealRule::NoEdIng.check("walk", "walker").is_ok());
+        assert!(StealRule::MustRearrange.check("eat", "treat").is_err());
+        assert!(StealRule::MustRearrange.check("eat", "tear").is_ok());
+
+        let rules = [StealRule::NoPlainS, StealRule::MustRearrange];
+        assert_eq!(
+            check_all(&rules, "cat", "cats"),
+            Err("Just adding an S to cat doesn't count.".to_string())
+        );
+        assert_eq!(
+            check_all(&rules, "rat", "rated"),
+            Err("rat has to be rearranged, not just extended.".to_string())
+        );
+        assert!(check_all(&rules, "rat", "star").is_ok());
+        assert!(check_all(&[], "cat", "cats").is_ok());
+    }
+}
@@ -0,0 +1,147 @@
+// Team and cooperative scoring for Set rooms.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A team as configured when the room is created.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamSetup {
+    pub name: String,
+    /// Names of players placed on this team; anyone else is balanced in as they join
+    pub members: Vec<String>,
+}
+
+/// How finds are totalled.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Scoring {
+    #[default]
+    Individual,
+    Teams {
+        #[serde(default)]
+        teams: Vec<TeamSetup>,
+    },
+    /// The whole room works together to clear the deck before the clock runs out
+    Coop {
+        #[serde(default)]
+        time_limit_secs: Option<u64>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Team {
+    pub name: String,
+    #[serde(skip)]
+    member_names: Vec<String>,
+    pub members: Vec<Uuid>,
+    pub score: u32,
+}
+
+/// Teams built from the room settings; at least two, with default names when none are given.
+pub fn build_teams(setup: &[TeamSetup]) -> Vec<Team> {
+    let mut teams: Vec<Team> = setup
+        .iter()
+        .enumerate()
+        .map(|(i, t)| Team {
+            name: if t.name.is_empty() {
+                format!("Team {}", i + 1)
+            } else {
+                t.name.clone()
+            },
+            member_names: t.members.clone(),
+            members: Vec::new(),
+            score: 0,
+        })
+        .collect();
+    for name in ["Red", "Blue"].iter().skip(teams.len()) {
+        teams.push(Team {
+            name: name.to_string(),
+            member_names: Vec::new(),
+            members: Vec::new(),
+            score: 0,
+        });
+    }
+    teams
+}
+
+/// Put a player on the team that lists them by name, or else on the smallest team.
+/// Returns the team's index.
+pub fn assign(teams: &mut [Team], player_id: Uuid, player_name: &str) -> usize {
+    if let Some(i) = teams.iter().position(|t| t.members.contains(&player_id)) {
+        return i;
+    }
+    let index = teams
+        .iter()
+        .position(|t| t.member_names.iter().any(|n| n == player_name))
+        .unwrap_or_else(|| {
+            (0..teams.len())
+                .min_by_key(|&i| teams[i].members.len())
+                .unwrap_or(0)
+        });
+    teams[index].members.push(player_id);
+    index
+}
+
+pub fn team_of(teams: &[Team], player_id: Uuid) -> Option<usize> {
+    teams.iter().position(|t| t.members.contains(&player_id))
+}
+
+/// Shared progress for a cooperative game.
+#[derive(Clone, Debug, Serialize)]
+pub struct CoopProgress {
+    pub started: DateTime<Utc>,
+    pub time_limit_secs: Option<u64>,
+    pub combined_score: u32,
+    /// Set when the game ends: whether the deck was cleared in time, and how long it took
+    pub cleared: Option<bool>,
+    pub elapsed_ms: Option<i64>,
+}
+
+impl CoopProgress {
+    pub fn new(time_limit_secs: Option<u64>) -> Self {
+        CoopProgress {
+            started: Utc::now(),
+            time_limit_secs,
+            combined_score: 0,
+            cleared: None,
+            elapsed_ms: None,
+        }
+    }
+
+    pub fn is_out_of_time(&self) -> bool {
+        self.time_limit_secs
+            .is_some_and(|limit| (Utc::now() - self.started).num_seconds() >= limit as i64)
+    }
+
+    pub fn finish(&mut self, cleared: bool) {
+        if self.cleared.is_none() {
+            self.cleared = Some(cleared);
+            self.elapsed_ms = Some((Utc::now() - self.started).num_milliseconds());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignment_follows_setup_then_balances() {
+        let setup = vec![TeamSetup {
+            name: "Owls".to_string(),
+            members: vec!["Alice".to_string(), "Bob".to_string()],
+        }];
+        let mut teams = build_teams(&setup);
+        assert_eq!(teams.len(), 2);
+        assert_eq!(teams[1].name, "Blue");
+
+        let (alice, bob, carol) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(assign(&mut teams, alice, "Alice"), 0);
+        assert_eq!(assign(&mut teams, bob, "Bob"), 0);
+        assert_eq!(assign(&mut teams, carol, "Carol"), 1);
+        // assigning again keeps the existing team
+        assert_eq!(assign(&mut teams, carol, "Carol"), 1);
+        assert_eq!(team_of(&teams, bob), Some(0));
+    }
+}
@@ -0,0 +1,163 @@
+// Bag sizes and tile distributions for Anagrams rooms. Presets reshape the language pack's
+// own distribution; a custom bag lists every tile count explicitly.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::language::LanguagePack;
+
+/// Largest custom bag a room may ask for, and the most of any single tile.
+pub const MAX_BAG_TILES: u32 = 400;
+pub const MAX_TILE_COUNT: u32 = 40;
+/// Smallest custom bag that still makes a game.
+pub const MIN_BAG_TILES: u32 = 20;
+
+const VOWELS: &[&str] = &["a", "e", "i", "o", "u", "ä", "ö", "ü"];
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "preset")]
+pub enum BagPreset {
+    /// About half the standard bag, for quick games
+    Short,
+    /// The language pack's distribution as is
+    #[default]
+    Standard,
+    /// 144 tiles, in the pack's proportions
+    Bananagrams,
+    /// Half again as many vowels as standard
+    VowelHeavy,
+    Custom {
+        counts: BTreeMap<String, u32>,
+    },
+}
+
+/// Scale `distribution` to exactly `total` tiles, keeping at least one of every tile.
+/// Leftover tiles go to the letters with the largest rounding remainders.
+fn scale(distribution: &[(String, u32)], total: u32) -> Vec<(String, u32)> {
+    let current: u32 = distribution.iter().map(|(_, n)| n).sum();
+    let mut scaled: Vec<(String, u32, f64)> = distribution
+        .iter()
+        .map(|(tile, n)| {
+            let exact = *n as f64 * total as f64 / current as f64;
+            (tile.clone(), (exact.floor() as u32).max(1), exact.fract())
+        })
+        .collect();
+    let assigned: u32 = scaled.iter().map(|(_, n, _)| n).sum();
+    let mut order: Vec<usize> = (0..scaled.len()).collect();
+    order.sort_by(|&a, &b| scaled[b].2.total_cmp(&scaled[a].2));
+    for i in order
+        .into_iter()
+        .take(total.saturating_sub(assigned) as usize)
+    {
+        scaled[i].1 += 1;
+    }
+    scaled.into_iter().map(|(tile, n, _)| (tile, n)).collect()
+}
+
+/// The tile counts a room's bag is filled with, or why a custom bag was refused.
+pub fn distribution(
+    language: &LanguagePack,
+    preset: &BagPreset,
+) -> Result<Vec<(String, u32)>, String> {
+    let standard = language.distribution();
+    let total: u32 = standard.iter().map(|(_, n)| n).sum();
+    Ok(match preset {
+        BagPreset::Standard => standard,
+        BagPreset::Short => scale(&standard, total.div_ceil(2)),
+        BagPreset::Bananagrams => scale(&standard, 144),
+        BagPreset::VowelHeavy => standard
+            .into_iter()
+            .map(|(tile, n)| {
+                let n = if VOWELS.contains(&tile.as_str()) {
+                    (n * 3).div_ceil(2)
+                } else {
+                    n
+                };
+                (tile, n)
+            })
+            .collect(),
+        BagPreset::Custom { counts } => {
+            // "E" and "e" are the same tile, so add up every spelling before checking limits
+            let mut merged: BTreeMap<String, u32> = BTreeMap::new();
+            for (tile, n) in counts {
+                let tile = language.normalize(tile);
+                if !language.has_tile(&tile) {
+                    return Err(format!("There is no {} tile in {}.", tile, language.name));
+                }
+                *merged.entry(tile).or_insert(0) += n;
+            }
+            let mut custom = Vec::new();
+            for (tile, n) in merged {
+                if n > MAX_TILE_COUNT {
+                    return Err(format!(
+                        "At most {} of a tile are allowed ({} has {}).",
+                        MAX_TILE_COUNT, tile, n
+                    ));
+                }
+                if n > 0 {
+                    custom.push((tile, n));
+                }
+            }
+            let total: u32 = custom.iter().map(|(_, n)| n).sum();
+            if !(MIN_BAG_TILES..=MAX_BAG_TILES).contains(&total) {
+                return Err(format!(
+                    "A custom bag needs between {} and {} tiles (this one has {}).",
+                    MIN_BAG_TILES, MAX_BAG_TILES, total
+                ));
+            }
+            custom
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::language;
+
+    fn total(distribution: &[(String, u32)]) -> u32 {
+        distribution.iter().map(|(_, n)| n).sum()
+    }
+
+    fn count(distribution: &[(String, u32)], tile: &str) -> u32 {
+        distribution
+            .iter()
+            .find(|(t, _)| t == tile)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    #[test]
+    fn test_presets_and_custom_validation() {
+        let english = language::default_pack();
+        let standard = distribution(english, &BagPreset::Standard).unwrap();
+        assert_eq!(total(&standard), 143);
+        assert_eq!(
+            total(&distribution(english, &BagPreset::Short).unwrap()),
+            72
+        );
+        let bananagrams = distribution(english, &BagPreset::Bananagrams).unwrap();
+        assert_eq!(total(&bananagrams), 144);
+        assert!(bananagrams.iter().all(|(_, n)| *n >= 1));
+        let vowels = distribution(english, &BagPreset::VowelHeavy).unwrap();
+        assert_eq!(count(&vowels, "e"), 27);
+        assert_eq!(count(&vowels, "t"), count(&standard, "t"));
+
+        let custom = |pairs: &[(&str, u32)]| BagPreset::Custom {
+            counts: pairs.iter().map(|(t, n)| (t.to_string(), *n)).collect(),
+        };
+        assert!(distribution(english, &custom(&[("E", 10), ("t", 10)])).is_ok());
+        assert!(distribution(english, &custom(&[("e", 10)])).is_err());
+        assert!(distribution(english, &custom(&[("e", 41)])).is_err());
+        assert!(distribution(english, &custom(&[("ñ", 10), ("e", 10)])).is_err());
+    }
+
+    #[test]
+    fn test_custom_counts_merge_spellings_of_a_tile() {
+        let english = language::default_pack();
+        let custom = |pairs: &[(&str, u32)]| BagPreset::Custom {
+            counts: pairs.iter().map(|(t, n)| (t.to_string(), *n)).collect(),
+        };
+        let merged = distribution(english, &custom(&[("E", 10), ("e", 10)])).unwrap();
+        assert_eq!(merged, vec![("e".to_string(), 20)]);
+        assert!(distribution(english, &custom(&[("E", 40), ("e", 40)])).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// Scoring for Anagrams rooms. Scores are recomputed from the boards after every move, so
+// they stay live through steals and reverted challenges; the same breakdown is sent at game over.
+use serde::{Deserialize, Serialize};
+
+use super::anagrams::PlayerBoard;
+use super::language::LanguagePack;
+use super::Uuid;
+
+/// Words longer than this many tiles earn a point for each extra tile under the length bonus.
+const LENGTH_BONUS_FROM: usize = 5;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringScheme {
+    /// One point per word
+    #[default]
+    WordCount,
+    /// A word's tiles minus two, so a 3-letter word is worth 1
+    LettersMinusTwo,
+    /// The sum of the language's Scrabble tile values
+    TileValues,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScoringSettings {
+    pub scheme: ScoringScheme,
+    /// A point for every tile beyond five in a word
+    pub length_bonus: bool,
+    /// Points lost each time one of your words is stolen
+    pub steal_penalty: u32,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct WordPoints {
+    pub word: String,
+    pub points: i32,
+    pub length_bonus: i32,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct ScoreBreakdown {
+    pub player_id: Uuid,
+    pub name: String,
+    pub words: Vec<WordPoints>,
+    pub times_stolen_from: u32,
+    pub steal_penalty: i32,
+    /// Points spent on hints; the game fills this in
+    pub hint_penalty: i32,
+    pub total: i32,
+}
+
+impl ScoringSettings {
+    fn word_points(&self, language: &LanguagePack, word: &str) -> WordPoints {
+        let tiles = language.tiles(word).unwrap_or_default();
+        let points = match self.scheme {
+            ScoringScheme::WordCount => 1,
+            ScoringScheme::LettersMinusTwo => tiles.len().saturating_sub(2) as i32,
+            ScoringScheme::TileValues => tiles.iter().map(|t| language.value(t) as i32).sum(),
+        };
+        let length_bonus = if self.length_bonus {
+            tiles.len().saturating_sub(LENGTH_BONUS_FROM) as i32
+        } else {
+            0
+        };
+        WordPoints {
+            word: word.to_string(),
+            points,
+            length_bonus,
+        }
+    }
+
+    /// Score one player's board, given how many of their words have been stolen.
+    pub fn score(
+        &self,
+        language: &LanguagePack,
+        board: &PlayerBoard,
+        times_stolen_from: u32,
+    ) -> ScoreBreakdown {
+        let words: Vec<WordPoints> = board
+            .words
+            .iter()
+            .map(|word| self.word_points(language, word))
+            .collect();
+        let steal_penalty = (self.steal_penalty * times_stolen_from) as i32;
+        let total = words.iter().map(|w| w.points + w.length_bonus).sum::<i32>() - steal_penalty;
+        ScoreBreakdown {
+            player_id: board.player.id,
+            name: board.player.name.clone(),
+            words,
+            times_stolen_from,
+            steal_penalty,
+            hint_penalty: 0,
+            total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{language, player::Player};
+
+    fn board(words: &[&str]) -> PlayerBoard {
+        PlayerBoard {
+            player: Player::new("Ada".into(), Uuid::new_v4()),
+            words: words.iter().map(|w| w.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_schemes_bonus_and_penalty() {
+        let english = language::default_pack();
+        let board = board(&["cat", "quizzes"]);
+        let total =
+            |settings: ScoringSettings, stolen| settings.score(english, &board, stolen).total;
+
+        assert_eq!(total(ScoringSettings::default(), 0), 2);
+        let letters = ScoringSettings {
+            scheme: ScoringScheme::LettersMinusTwo,
+            ..Default::default()
+        };
+        assert_eq!(total(letters.clone(), 0), 1 + 5);
+        assert_eq!(
+            total(
+                ScoringSettings {
+                    scheme: ScoringScheme::TileValues,
+                    ..Default::default()
+                },
+                0
+            ),
+            5 + 10 + 1 + 1 + 10 + 10 + 1 + 1
+        );
+        let bonus = ScoringSettings {
+            length_bonus: true,
+            steal_penalty: 2,
+            ..letters
+        };
+        let breakdown = bonus.score(english, &board, 3);
+        assert_eq!(breakdown.words[1].length_bonus, 2);
+        assert_eq!(breakdown.steal_penalty, 6);
+        assert_eq!(breakdown.total, 1 + 5 + 2 - 6);
+    }
+}
@@ -6,6 +6,15 @@ mod user;
 async fn main() {
     pretty_env_logger::init();
 
+    let (word_lists, errors) = game::dictionary::startup_report();
+    println!("Word lists: {}", word_lists.join(", "));
+    for error in &errors {
+        eprintln!("Word list error: {}", error);
+    }
+
+    // load the lemmatizer up front rather than on the first steal
+    println!("Using the {} lemmatizer", game::anagrams::lemmatizer_name());
+
     let routes = router::setup_routes();
 
     println!("Listening on 0.0.0.0:3030");
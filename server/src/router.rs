@@ -3,13 +3,11 @@ use game::{Game, GameList};
 
 use crate::user::User;
 use futures::{future, SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use lazy_static::lazy_static;
 use std::{
-    collections::BTreeSet,
     convert::Infallible,
-    io::BufRead,
     sync::{Arc, RwLock},
 };
 use tokio::sync::broadcast::{self, Sender};
@@ -24,10 +22,127 @@ pub struct Message {
     pub data: String,
 }
 
+/// A game message addressed to a single player. It travels over the room's
+/// broadcast channel like any other message, but the socket forwarder only
+/// delivers it to the connection that identified itself as `recipient`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectMessage {
+    pub kind: String,
+    pub data: String,
+    pub recipient: Uuid,
+}
+
+#[derive(Deserialize)]
+struct Addressee {
+    recipient: Option<Uuid>,
+}
+
+/// Mark a player in a room as connected or not, e.g. so challenge votes only wait on players
+/// who are still here.
+fn set_player_connected(games: &GameList, room_id: Uuid, player_id: Uuid, connected: bool) {
+    if let Some(player) = games
+        .games
+        .iter()
+        .map(|g| g.get_details())
+        .find(|g| g.id == room_id)
+        .and_then(|g| g.players.iter().find(|p| p.id == player_id))
+    {
+        player.set_connected(connected);
+    }
+}
+
+/// The player a `join_player` message seats in the room, looked up by the name it joined with.
+fn joined_player(games: &GameList, room_id: Uuid, txt: &str) -> Option<Uuid> {
+    let parsed = serde_json::from_str::<serde_json::Value>(txt).ok()?;
+    if parsed.get("kind")?.as_str()? != "join_player" {
+        return None;
+    }
+    let data = serde_json::from_str::<serde_json::Value>(parsed.get("data")?.as_str()?).ok()?;
+    // the rooms seat a nameless join as "Anonymous"
+    let name = data
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Anonymous");
+    games
+        .games
+        .iter()
+        .map(|g| g.get_details())
+        .find(|g| g.id == room_id)?
+        .players
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.id)
+}
+
+/// Pass a message from a client connection to its room, noting which player the connection
+/// acts for: the message's `player_id` if it has one, or whoever a `join_player` seats.
+/// Anagrams clients that only chat never send a `player_id`, so joining is what binds them.
+fn handle_client_message(
+    games: &RwLock<GameList>,
+    room_id: Uuid,
+    connection_player: &RwLock<Option<Uuid>>,
+    txt: String,
+) {
+    if let Some(pid) = serde_json::from_str::<serde_json::Value>(&txt)
+        .ok()
+        .and_then(|v| v.get("player_id")?.as_str().map(str::to_string))
+        .and_then(|s| Uuid::parse_str(&s).ok())
+    {
+        *connection_player.write().unwrap() = Some(pid);
+        set_player_connected(&games.read().unwrap(), room_id, pid, true);
+    }
+    let mut guard = games.write().unwrap();
+    if let Some(game_mut) = guard
+        .games
+        .iter_mut()
+        .find(|g| g.copy_details().id == room_id)
+    {
+        game_mut.handle_game_socket_message(txt.clone());
+    }
+    if let Some(pid) = joined_player(&guard, room_id, &txt) {
+        *connection_player.write().unwrap() = Some(pid);
+        set_player_connected(&guard, room_id, pid, true);
+    }
+}
+
+/// Room settings from a `create_game` request. Missing or null settings mean the defaults;
+/// anything else has to parse, so a typo isn't silently played with default rules.
+fn parse_settings<T: DeserializeOwned + Default>(
+    settings: &serde_json::Value,
+) -> Result<T, String> {
+    if settings.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(settings.clone()).map_err(|e| format!("Invalid room settings: {}", e))
+}
+
+/// Returns false if `msg` is a `DirectMessage` meant for someone other than `player_id`.
+fn is_visible_to(msg: &str, player_id: Option<Uuid>) -> bool {
+    match serde_json::from_str::<Addressee>(msg) {
+        Ok(Addressee {
+            recipient: Some(recipient),
+        }) => player_id == Some(recipient),
+        _ => true,
+    }
+}
+
 pub fn setup_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     // create shared games list wrapped in Arc so filters/handlers can share it
     let games = Arc::new(RwLock::new(game::GameList::new()));
 
+    // drive bots and other time-based game logic
+    let games_for_ticker = games.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_millis(250));
+        loop {
+            interval.tick().await;
+            let mut guard = games_for_ticker.write().unwrap();
+            for game in guard.games.iter_mut() {
+                game.tick();
+            }
+        }
+    });
+
     // broadcast channel for lobby server -> clients
     let (tx, _) = broadcast::channel::<String>(64);
     let tx = Arc::new(tx);
@@ -39,6 +154,8 @@ pub fn setup_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::R
     // create two filters for games (one for lobby, one for game ws)
     let games_for_lobby = games.clone();
     let games_filter_for_lobby = warp::any().map(move || games_for_lobby.clone());
+    let games_for_analysis = games.clone();
+    let games_filter_for_analysis = warp::any().map(move || games_for_analysis.clone());
     let games_filter = warp::any().map(move || games.clone());
 
     let lobby_route = warp::path("lobby")
@@ -52,7 +169,12 @@ pub fn setup_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::R
         .and(games_filter)
         .and_then(handle_game_ws);
 
-    lobby_route.or(game_route)
+    let analysis_route = warp::path!("game" / String / "analysis")
+        .and(warp::get())
+        .and(games_filter_for_analysis)
+        .and_then(handle_game_analysis);
+
+    lobby_route.or(game_route).or(analysis_route)
 }
 
 async fn handle_lobby_ws(
@@ -63,6 +185,26 @@ async fn handle_lobby_ws(
     Ok(ws.on_upgrade(move |socket| client_lobby_connection(socket, tx, games)))
 }
 
+async fn handle_game_analysis(
+    room_id: String,
+    games: Arc<RwLock<GameList>>,
+) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let analysis = Uuid::parse_str(room_id.trim()).ok().and_then(|room_id| {
+        let guard = games.read().unwrap();
+        guard
+            .iter()
+            .find(|g| g.get_details().id == room_id)
+            .and_then(|g| g.analysis())
+    });
+    match analysis {
+        Some(analysis) => Ok(Box::new(warp::reply::json(&analysis))),
+        None => Ok(Box::new(warp::reply::with_status(
+            "Analysis not available",
+            warp::http::StatusCode::NOT_FOUND,
+        ))),
+    }
+}
+
 async fn handle_game_ws(
     room_id: String,
     ws: warp::ws::Ws,
@@ -89,7 +231,7 @@ async fn handle_game_ws(
     // on successful validation accept upgrade and run the connection handler;
     // wrap handler in an async block so we can log any internal errors.
     let games_clone = games.clone();
-    let room = room_id.clone();
+    let room = room_id;
     Ok(Box::new(ws.on_upgrade(move |socket| async move {
         client_game_connection(room, socket, games_clone).await;
     })))
@@ -116,13 +258,20 @@ async fn client_game_connection(
     let (mut ws_tx, mut ws_rx) = ws.split();
 
     if let Some(game_tx) = game_opt {
+        // the player this connection acts for, learned from incoming messages
+        let connection_player: Arc<RwLock<Option<Uuid>>> = Arc::new(RwLock::new(None));
         // subscribe to the game's broadcast channel
         let rx = game_tx.subscribe();
         // spawn a task that forwards game broadcast messages to this websocket
         let mut send_rx = rx;
         let mut ws_tx_owned = ws_tx;
+        let send_player = connection_player.clone();
         let send_handle = tokio::spawn(async move {
             while let Ok(msg) = send_rx.recv().await {
+                let player_id = *send_player.read().unwrap();
+                if !is_visible_to(&msg, player_id) {
+                    continue;
+                }
                 if ws_tx_owned
                     .send(warp::ws::Message::text(msg))
                     .await
@@ -144,19 +293,16 @@ async fn client_game_connection(
         while let Some(Ok(message)) = ws_rx.next().await {
             if message.is_text() {
                 let txt = message.to_str().unwrap_or_default().to_string();
-                let mut guard = games.write().unwrap();
-                if let Some(game_mut) = guard
-                    .games
-                    .iter_mut()
-                    .find(|g| g.copy_details().id == room_id)
-                {
-                    game_mut.handle_game_socket_message(txt);
-                }
+                handle_client_message(&games, room_id, &connection_player, txt);
             }
         }
 
         // if loop ended, abort the sender task (it owns ws_tx)
         send_handle.abort();
+        let player_id = *connection_player.read().unwrap();
+        if let Some(pid) = player_id {
+            set_player_connected(&games.read().unwrap(), room_id, pid, false);
+        }
     } else {
         // fallback: just echo messages back
         while let Some(Ok(message)) = ws_rx.next().await {
@@ -176,22 +322,13 @@ async fn client_game_connection(
 }
 
 lazy_static! {
-    static ref ADJECTIVE_LIST: Arc<Vec<String>> = {
-        let mut vec: Vec<String> = Vec::with_capacity(2000);
-        // Build path relative to this crate
-        let path = format!("{}/src/game/english-adjectives.txt", env!("CARGO_MANIFEST_DIR"));
-        if let Ok(f) = std::fs::File::open(&path) {
-            let reader = std::io::BufReader::new(f);
-            for line in reader.lines().flatten() {
-                let word = line.trim().to_lowercase();
-                if word.is_empty() {
-                    continue;
-                }
-                vec.push(word);
-            }
-        }
-        Arc::new(vec)
-    };
+    static ref ADJECTIVE_LIST: Arc<Vec<String>> = Arc::new(
+        include_str!("game/english-adjectives.txt")
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect()
+    );
 }
 
 async fn client_lobby_connection(
@@ -231,6 +368,9 @@ async fn client_lobby_connection(
                             struct CreatePayload {
                                 creator: String,
                                 game_type: String,
+                                // game-specific room settings; omitted fields use defaults
+                                #[serde(default)]
+                                settings: serde_json::Value,
                             }
                             if let Ok(payload) = serde_json::from_str::<CreatePayload>(&parsed.data)
                             {
@@ -258,13 +398,45 @@ async fn client_lobby_connection(
                                     capitalize_first(&payload.game_type)
                                 );
 
-                                let new_game: Box<dyn Game>;
-                                match &payload.game_type[..] {
-                                    "anagrams" => {
-                                        new_game = Box::new(game::anagrams::Anagrams::new(name))
+                                let new_game: Result<Box<dyn Game>, String> = match &payload
+                                    .game_type[..]
+                                {
+                                    "anagrams" => parse_settings(&payload.settings).and_then(
+                                        |settings: game::anagrams::AnagramsSettings| {
+                                            settings.check()?;
+                                            Ok(Box::new(game::anagrams::Anagrams::new(
+                                                name, settings,
+                                            ))
+                                                as Box<dyn Game>)
+                                        },
+                                    ),
+                                    _ => parse_settings(&payload.settings).map(
+                                        |settings| -> Box<dyn Game> {
+                                            Box::new(game::set::Set::new(name, creator, settings))
+                                        },
+                                    ),
+                                };
+                                let new_game = match new_game {
+                                    Ok(new_game) => new_game,
+                                    Err(error) => {
+                                        // addressed like game_created: the creator's client picks it up
+                                        #[derive(serde::Serialize)]
+                                        struct CreateErrorPayload {
+                                            creator: String,
+                                            error: String,
+                                        }
+                                        let msg = Message {
+                                            kind: "create_game_error".into(),
+                                            data: serde_json::to_string(&CreateErrorPayload {
+                                                creator: payload.creator,
+                                                error,
+                                            })
+                                            .unwrap_or_default(),
+                                        };
+                                        let _ = tx2.send(serde_json::to_string(&msg).unwrap());
+                                        continue;
                                     }
-                                    _ => new_game = Box::new(game::set::Set::new(name, creator)),
-                                }
+                                };
                                 let game_id = new_game.copy_details().id;
                                 {
                                     let mut guard = games.write().unwrap();
@@ -311,6 +483,61 @@ async fn client_lobby_connection(
                             let json = serde_json::to_string(&msg).unwrap();
                             let _ = tx2.send(json);
                         }
+                        "dictionaries" => {
+                            // word lists a new Anagrams room can be created with
+                            let msg = Message {
+                                kind: "dictionaries".into(),
+                                data: serde_json::to_string(&game::dictionary::names())
+                                    .unwrap_or_default(),
+                            };
+                            let json = serde_json::to_string(&msg).unwrap();
+                            let _ = tx2.send(json);
+                        }
+                        "languages" => {
+                            // language packs a new Anagrams room can be created with: those
+                            // whose word list is installed
+                            #[derive(serde::Serialize)]
+                            struct LanguageInfo {
+                                code: &'static str,
+                                name: &'static str,
+                                dictionary: &'static str,
+                            }
+                            let languages: Vec<LanguageInfo> = game::language::available()
+                                .into_iter()
+                                .map(|pack| LanguageInfo {
+                                    code: pack.code,
+                                    name: pack.name,
+                                    dictionary: pack.dictionary,
+                                })
+                                .collect();
+                            let msg = Message {
+                                kind: "languages".into(),
+                                data: serde_json::to_string(&languages).unwrap_or_default(),
+                            };
+                            let json = serde_json::to_string(&msg).unwrap();
+                            let _ = tx2.send(json);
+                        }
+                        "daily_leaderboard" => {
+                            // data may name a date (YYYY-MM-DD); defaults to today
+                            let date =
+                                chrono::NaiveDate::parse_from_str(parsed.data.trim(), "%Y-%m-%d")
+                                    .unwrap_or_else(|_| game::daily::today());
+                            #[derive(serde::Serialize)]
+                            struct LeaderboardPayload {
+                                date: chrono::NaiveDate,
+                                entries: Vec<game::daily::DailyEntry>,
+                            }
+                            let payload = LeaderboardPayload {
+                                date,
+                                entries: game::daily::leaderboard(date),
+                            };
+                            let msg = Message {
+                                kind: "daily_leaderboard".into(),
+                                data: serde_json::to_string(&payload).unwrap_or_default(),
+                            };
+                            let json = serde_json::to_string(&msg).unwrap();
+                            let _ = tx2.send(json);
+                        }
                         "delete_game" => {
                             // data should be a JSON object with game id
                             #[derive(serde::Deserialize)]
@@ -393,3 +620,100 @@ async fn client_lobby_connection(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A room with one Anagrams game in it.
+    fn anagrams_room(settings: serde_json::Value) -> (RwLock<GameList>, Uuid) {
+        let game = game::anagrams::Anagrams::new(
+            "testgame".to_string(),
+            parse_settings(&settings).unwrap(),
+        );
+        let room_id = game.copy_details().id;
+        let mut games = GameList::new();
+        games.add_game(Box::new(game));
+        (RwLock::new(games), room_id)
+    }
+
+    #[test]
+    fn test_joining_binds_the_connection_so_chat_only_players_disconnect() {
+        let (games, room_id) = anagrams_room(serde_json::Value::Null);
+        let connection_player = RwLock::new(None);
+        // exactly what the client sends on joining, then a challenge vote over chat
+        for txt in [
+            r#"{"kind":"join_player","data":"{\"name\":\"Alice\"}"}"#,
+            r#"{"kind":"chat","data":"{\"sender\":\"Alice\",\"message\":\"/maintain\"}"}"#,
+        ] {
+            handle_client_message(&games, room_id, &connection_player, txt.to_string());
+        }
+        let alice = {
+            let guard = games.read().unwrap();
+            guard.games[0].get_details().players[0].clone()
+        };
+        assert_eq!(*connection_player.read().unwrap(), Some(alice.id));
+        assert!(alice.is_connected());
+
+        // the connection closes
+        let pid = connection_player.read().unwrap().unwrap();
+        set_player_connected(&games.read().unwrap(), room_id, pid, false);
+        assert!(!alice.is_connected());
+    }
+
+    #[test]
+    fn test_hints_reach_the_chat_player_who_asked() {
+        // a bag of nothing but Zs, dealt by flipping, so ZZZ is on after two flips
+        let (games, room_id) = anagrams_room(serde_json::json!({
+            "bag": {"preset": "custom", "counts": {"z": 20}},
+            "deal": {"mode": "turns"},
+        }));
+        let mut rx = games.read().unwrap().games[0]
+            .get_details()
+            .broadcast_tx
+            .subscribe();
+        let connection_player = RwLock::new(None);
+        for txt in [
+            r#"{"kind":"join_player","data":"{\"name\":\"Alice\"}"}"#,
+            r#"{"kind":"chat","data":"{\"sender\":\"Alice\",\"message\":\"/flip\"}"}"#,
+            r#"{"kind":"chat","data":"{\"sender\":\"Alice\",\"message\":\"/flip\"}"}"#,
+            r#"{"kind":"chat","data":"{\"sender\":\"Alice\",\"message\":\"/hint\"}"}"#,
+        ] {
+            handle_client_message(&games, room_id, &connection_player, txt.to_string());
+        }
+        let alice = *connection_player.read().unwrap();
+        assert!(alice.is_some());
+
+        let mut hints = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            // a direct message: Alice's connection forwards it and nobody else's does
+            if is_visible_to(&msg, alice) && !is_visible_to(&msg, None) {
+                hints.push(msg);
+            }
+        }
+        assert_eq!(hints.len(), 1);
+        assert!(hints[0].contains("Hint: ZZZ from the pot."));
+    }
+
+    #[test]
+    fn test_malformed_room_settings_are_refused() {
+        let defaults: game::anagrams::AnagramsSettings =
+            parse_settings(&serde_json::Value::Null).unwrap();
+        assert_eq!(defaults.language, "en");
+        let custom: game::anagrams::AnagramsSettings =
+            parse_settings(&serde_json::json!({"combination_steals": true})).unwrap();
+        assert!(custom.combination_steals);
+        assert!(
+            parse_settings::<game::anagrams::AnagramsSettings>(&serde_json::json!({
+                "combination_steals": "yes"
+            }))
+            .is_err()
+        );
+        assert!(
+            parse_settings::<game::set::SetSettings>(&serde_json::json!({
+                "hints": "sometimes"
+            }))
+            .is_err()
+        );
+    }
+}
@@ -3,9 +3,6 @@ set -e
 
 cd "$(dirname "$0")"
 
-# Activate venv
-source venv/bin/activate
-
 cargo build --release
 # Run the server
-./target/release/rust_ws_server
\ No newline at end of file
+./target/release/rust_ws_server